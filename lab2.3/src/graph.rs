@@ -1,19 +1,14 @@
 use std::fmt::Display;
 
-use rand::{Rng, SeedableRng, rngs::SmallRng};
-
-// $\mathtt{\overline{n_1 n_2 n_3 n_4} = 4228}$
-pub const ROWS: &[usize] = &[4, 3, 5];
-pub const VERTEX_COUNT: usize = 12; // $\mathtt{10 + n_3} = 12$
-const RANDOM_SEED: u64 = 4228;
+use crate::rng::Xoshiro256;
 
 #[derive(Clone)]
-pub struct AdjMatrix(pub [[u8; VERTEX_COUNT]; VERTEX_COUNT]);
+pub struct AdjMatrix(pub Vec<Vec<u8>>);
 
 impl Display for AdjMatrix {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for i in 0..VERTEX_COUNT {
-            for j in 0..VERTEX_COUNT {
+        for i in 0..self.0.len() {
+            for j in 0..self.0.len() {
                 if i == j {
                     write!(f, "\x1b[31m{}\x1b[0m ", self.0[i][j])?;
                     continue;
@@ -26,25 +21,99 @@ impl Display for AdjMatrix {
     }
 }
 
-pub fn generate_dir_matrix() -> AdjMatrix {
-    let mut rng = SmallRng::seed_from_u64(RANDOM_SEED);
-    let iter = std::iter::repeat_with(move || rng.random_range(0.0..2.0));
+impl AdjMatrix {
+    /// Every `(from, to)` pair with a 1 in the matrix.
+    pub fn edges(&self) -> Vec<(usize, usize)> {
+        (0..self.0.len())
+            .flat_map(|i| (0..self.0.len()).map(move |j| (i, j)))
+            .filter(|(i, j)| self.0[*i][*j] == 1)
+            .collect()
+    }
 
-    // $\mathtt{1 - n_3 * 0.02 - n_4 * 0.005 - 0.25}$
-    const K: f32 = 1.0 - 2.0 * 0.02 - 8.0 * 0.005 - 0.25;
+    pub fn degree_out(&self, vertex: usize) -> usize {
+        self.0[vertex].iter().filter(|i| **i == 1).count()
+    }
+
+    pub fn degree_in(&self, vertex: usize) -> usize {
+        self.0.iter().filter(|row| row[vertex] == 1).count()
+    }
+}
 
-    let mut values = iter.take(VERTEX_COUNT * VERTEX_COUNT);
-    let matrix = std::array::from_fn(|_| std::array::from_fn(|_| values.next().unwrap() * K));
+pub fn generate_dir_matrix(size: usize, seed: u64, k: f32) -> AdjMatrix {
+    let mut rng = Xoshiro256::seed_from_u64(seed);
+    let iter = std::iter::repeat_with(move || rng.random_range(0.0..2.0));
 
-    AdjMatrix(matrix.map(|row| row.map(|value| f32::min(1.0, value) as u8)))
+    AdjMatrix(
+        iter.take(size * size)
+            .map(|value| f32::min(1.0, value * k) as u8)
+            .collect::<Vec<_>>()
+            .chunks(size)
+            .map(|row| row.to_vec())
+            .collect(),
+    )
 }
 
 pub fn convert_to_undir(dir_matrix: &AdjMatrix) -> AdjMatrix {
     let mut undir_matrix = dir_matrix.clone();
-    for i in 0..VERTEX_COUNT {
-        for j in (i + 1)..VERTEX_COUNT {
+    for i in 0..dir_matrix.0.len() {
+        for j in (i + 1)..dir_matrix.0.len() {
             undir_matrix.0[j][i] = undir_matrix.0[i][j];
         }
     }
     undir_matrix
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variant::{Lab, Variant};
+
+    fn matrix(rows: &[[u8; 12]]) -> Vec<Vec<u8>> {
+        rows.iter().map(|row| row.to_vec()).collect()
+    }
+
+    // the matrices `main` prints for the default variant
+    #[test]
+    fn variant_4228() {
+        let variant = Variant::new(4228).unwrap();
+        let dir_matrix = generate_dir_matrix(
+            variant.vertex_count(),
+            variant.seed(),
+            variant.k(Lab::Representation),
+        );
+        assert_eq!(
+            dir_matrix.0,
+            matrix(&[
+                [0, 1, 1, 0, 0, 0, 1, 0, 0, 0, 1, 0],
+                [0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 1, 1],
+                [0, 0, 1, 0, 0, 1, 1, 0, 0, 0, 0, 0],
+                [0, 0, 1, 1, 0, 1, 0, 1, 1, 1, 0, 1],
+                [0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 1, 0],
+                [0, 0, 0, 0, 0, 1, 0, 1, 0, 1, 0, 0],
+                [0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 1, 0],
+                [0, 0, 1, 0, 0, 0, 0, 0, 0, 1, 0, 0],
+                [0, 1, 0, 0, 0, 1, 0, 0, 0, 0, 1, 0],
+                [1, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+                [0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1, 1],
+                [1, 0, 1, 1, 0, 1, 0, 0, 1, 1, 0, 1],
+            ])
+        );
+        assert_eq!(
+            convert_to_undir(&dir_matrix).0,
+            matrix(&[
+                [0, 1, 1, 0, 0, 0, 1, 0, 0, 0, 1, 0],
+                [1, 0, 0, 1, 0, 0, 0, 1, 0, 0, 1, 1],
+                [1, 0, 1, 0, 0, 1, 1, 0, 0, 0, 0, 0],
+                [0, 1, 0, 1, 0, 1, 0, 1, 1, 1, 0, 1],
+                [0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 1, 0],
+                [0, 0, 1, 1, 0, 1, 0, 1, 0, 1, 0, 0],
+                [1, 0, 1, 0, 1, 0, 0, 0, 0, 0, 1, 0],
+                [0, 1, 0, 1, 0, 1, 0, 0, 0, 1, 0, 0],
+                [0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 1, 0],
+                [0, 0, 0, 1, 0, 1, 0, 1, 0, 0, 0, 0],
+                [1, 1, 0, 0, 1, 0, 1, 0, 1, 0, 1, 1],
+                [0, 1, 0, 1, 0, 0, 0, 0, 0, 0, 1, 1],
+            ])
+        );
+    }
+}
@@ -1,91 +1,96 @@
 use draw::draw_text;
-use graph::{AdjMatrix, ROWS, VERTEX_COUNT};
+use graph::AdjMatrix;
+use layout::{Arrangement, Hover, VertexPos};
 use raylib::{color::Color, prelude::*};
+use variant::{Lab, Variant};
+use view::View;
 mod draw;
 mod graph;
+mod layout;
+mod rng;
+mod variant;
+mod view;
 
 const WIN_WIDTH: i32 = 800;
 const WIN_HEIGHT: i32 = 600;
 const WIN_MARGIN: f32 = 0.8;
+const TOOLTIP_OFFSET: Vector2 = Vector2 { x: 12.0, y: 12.0 };
 
-#[derive(Clone, Copy)]
-struct VertexPos {
-    v: Vector2,
-    row: usize,
-    col: usize,
-}
-
-fn draw_all_vertices(d: &mut RaylibDrawHandle, font: &Font) -> [VertexPos; VERTEX_COUNT] {
-    fn current_position(index: usize) -> (usize, usize) {
-        let mut cumulative = 0;
-        for (row, &count) in ROWS.iter().enumerate() {
-            if index < cumulative + count {
-                return (row, index - cumulative);
-            }
-            cumulative += count;
-        }
-        (usize::MAX, usize::MAX)
-    }
-    let winwidth = WIN_WIDTH as f32 * WIN_MARGIN;
-    let winheight = WIN_HEIGHT as f32 * WIN_MARGIN;
-    let vertex_coords: [VertexPos; VERTEX_COUNT] = std::array::from_fn(|i| {
-        let (row, col) = current_position(i);
-
-        let x_offset = (WIN_WIDTH as f32 - winwidth) * 0.5;
-        let y_offset = (WIN_HEIGHT as f32 - winheight) * 0.5;
-        VertexPos {
-            v: Vector2 {
-                x: (winwidth / (ROWS[row] - 1) as f32 * col as f32) + x_offset,
-                y: (winheight / (ROWS.len() - 1) as f32 * row as f32) + y_offset,
-            },
-            row,
-            col,
-        }
-    });
-
-    (0..VERTEX_COUNT).for_each(|i| {
+fn draw_all_vertices(d: &mut impl RaylibDraw, font: &Font, vertex_coords: &[VertexPos]) {
+    (0..vertex_coords.len()).for_each(|i| {
         draw::draw_vertex(d, vertex_coords[i].v, &((i + 1).to_string()), font);
     });
-
-    vertex_coords
 }
 
 fn draw_all_edges(
-    d: &mut RaylibDrawHandle,
+    d: &mut impl RaylibDraw,
     adj_matrix: &AdjMatrix,
     vertex_coords: &[VertexPos],
     directed: bool,
 ) {
-    (0..VERTEX_COUNT).for_each(|i| {
+    (0..vertex_coords.len()).for_each(|i| {
         let lower = if directed { 0 } else { i };
-        (lower..VERTEX_COUNT).for_each(|j| {
+        (lower..vertex_coords.len()).for_each(|j| {
             let origin = vertex_coords[i];
             let destination = vertex_coords[j];
-            let row_absdiff = (destination.row as i64 - origin.row as i64).abs();
-            let col_absdiff = (destination.col as i64 - origin.col as i64).abs();
 
             if adj_matrix.0[i][j] == 1 {
                 if i == j {
                     draw::draw_looping_edge(d, origin.v);
-                } else if (adj_matrix.0[j][i] == 1 && directed) // symmetric
-                    || (row_absdiff == 0 && col_absdiff > 1) // same row, goes through others
-                    || (col_absdiff == 0 && row_absdiff > 1) // same col, goes through others
-                    || (origin.v.x == destination.v.x) // same x coordinate, yes, still possible
-                    || col_absdiff >= 3
-                // honestly ^ whatever this is
-                {
-                    draw::draw_angled_edge(d, origin.v, destination.v, directed);
                 } else {
-                    draw::draw_straight_edge(d, origin.v, destination.v, directed);
+                    let min_bend = if directed && adj_matrix.0[j][i] == 1 {
+                        layout::ANTIPARALLEL_BEND
+                    } else {
+                        0.0
+                    };
+                    let bend = layout::edge_bend(vertex_coords, i, j, min_bend);
+                    if bend == 0.0 {
+                        draw::draw_straight_edge(d, origin.v, destination.v, directed);
+                    } else {
+                        draw::draw_curved_edge(d, origin.v, destination.v, bend, directed);
+                    }
                 }
             }
         })
     })
 }
 
+/// Tooltip title first, then the details for the side panel.
+fn inspect(adj_matrix: &AdjMatrix, directed: bool, hover: Hover) -> Vec<String> {
+    match hover {
+        Hover::Vertex(vertex) if directed => vec![
+            format!("Vertex {}", vertex + 1),
+            format!("In: {}", adj_matrix.degree_in(vertex)),
+            format!("Out: {}", adj_matrix.degree_out(vertex)),
+        ],
+        Hover::Vertex(vertex) => vec![
+            format!("Vertex {}", vertex + 1),
+            // a loop adds 2
+            format!(
+                "Degree: {}",
+                adj_matrix.degree_out(vertex) + adj_matrix.0[vertex][vertex] as usize
+            ),
+        ],
+        Hover::Edge(from, to) => vec![format!(
+            "Edge {} {} {}",
+            from + 1,
+            if directed { "->" } else { "--" },
+            to + 1
+        )],
+    }
+}
+
 fn main() {
-    let dir_matrix = graph::generate_dir_matrix();
+    let variant = Variant::from_args();
+    let rows = variant.rows();
+
+    let dir_matrix = graph::generate_dir_matrix(
+        variant.vertex_count(),
+        variant.seed(),
+        variant.k(Lab::Representation),
+    );
     let undir_matrix = graph::convert_to_undir(&dir_matrix);
+    println!("Variant: {}", variant);
     println!("Directed adjacency matrix:\n{}", dir_matrix);
     println!("Undirected adjacency matrix:\n{}", undir_matrix);
 
@@ -93,29 +98,68 @@ fn main() {
         .size(WIN_WIDTH, WIN_HEIGHT)
         .log_level(TraceLogLevel::LOG_WARNING)
         .title("ASD Lab 2.3")
+        .resizable()
         .build();
+    rl.set_window_min_size(WIN_WIDTH / 2, WIN_HEIGHT / 2);
 
     let font = rl.load_font(&thread, "FiraCode-Regular.ttf").unwrap();
 
+    let layout_file = layout::file_name(&variant, None);
+    let mut view = View::new(&rl);
+    let mut vertex_coords = layout::load_or_rows(&layout_file, &rows, view.size);
+    let mut drag = layout::Drag::new();
+    let mut arrangement = Arrangement::Rows;
+
     while !rl.window_should_close() {
+        if let Some(old_size) = view.update(&rl) {
+            layout::rescale(&mut vertex_coords, old_size, view.size);
+        }
+        drag.update(&rl, view.mouse(&rl), &mut vertex_coords);
+        if rl.is_key_pressed(KeyboardKey::KEY_F5) {
+            match layout::save(&layout_file, &vertex_coords) {
+                Ok(()) => println!("Layout saved to {}", layout_file),
+                Err(err) => eprintln!("Could not save {}: {}", layout_file, err),
+            }
+        } else if rl.is_key_pressed(KeyboardKey::KEY_F6) {
+            arrangement = arrangement.next();
+            println!("Arrangement: {:?}", arrangement);
+            vertex_coords =
+                layout::arrange(arrangement, &rows, &dir_matrix.edges(), &[], view.size);
+        }
+        let directed = rl.is_key_down(KeyboardKey::KEY_SPACE);
+        let matrix = if directed { &dir_matrix } else { &undir_matrix };
+        let mouse = view.mouse(&rl);
+        let inspection = layout::hover(&vertex_coords, &matrix.edges(), directed, mouse)
+            .map(|hover| inspect(matrix, directed, hover));
         let mut d = rl.begin_drawing(&thread);
 
         d.clear_background(Color::WHITE);
+        {
+            let mut d = d.begin_mode2D(view.camera);
+            draw_all_vertices(&mut d, &font, &vertex_coords);
+            draw_all_edges(&mut d, matrix, &vertex_coords, directed);
+            if let Some(lines) = &inspection {
+                draw::draw_panel(&mut d, &font, &lines[..1], mouse + TOOLTIP_OFFSET);
+            }
+        }
+
+        let mut d = d.begin_mode2D(view.overlay());
         draw_text(
             &mut d,
             &font,
             "<Space>",
             Vector2 {
-                x: 0.8 * WIN_WIDTH as f32,
-                y: 0.95 * WIN_HEIGHT as f32,
+                x: 0.8 * view.size.x,
+                y: 0.95 * view.size.y,
             },
         );
-
-        let vertex_coords = draw_all_vertices(&mut d, &font);
-        if d.is_key_down(KeyboardKey::KEY_SPACE) {
-            draw_all_edges(&mut d, &dir_matrix, &vertex_coords, true);
-        } else {
-            draw_all_edges(&mut d, &undir_matrix, &vertex_coords, false);
+        if let Some(lines) = &inspection {
+            let panel = draw::panel_size(&font, lines);
+            let position = Vector2 {
+                x: 0.99 * view.size.x - panel.x,
+                y: 0.01 * view.size.y,
+            };
+            draw::draw_panel(&mut d, &font, lines, position);
         }
     }
 }
@@ -0,0 +1,88 @@
+use raylib::prelude::*;
+
+const ZOOM_STEP: f32 = 1.1;
+const ZOOM_MIN: f32 = 0.2;
+const ZOOM_MAX: f32 = 8.0;
+
+/// Camera over the layout: mouse wheel zooms around the cursor, dragging with
+/// the middle button pans and Home resets both. Layout coordinates are
+/// logical pixels, the camera scales them (and with them `VERTEX_RADIUS` and
+/// every font size) by the monitor's DPI factor.
+pub struct View {
+    pub camera: Camera2D,
+    /// Window size in layout coordinates.
+    pub size: Vector2,
+    zoom: f32,
+    dpi: f32,
+}
+
+fn dpi(rl: &RaylibHandle) -> f32 {
+    rl.get_window_scale_dpi().x.max(1.0)
+}
+
+fn window_size(rl: &RaylibHandle) -> Vector2 {
+    Vector2 {
+        x: rl.get_screen_width() as f32,
+        y: rl.get_screen_height() as f32,
+    } / dpi(rl)
+}
+
+impl View {
+    pub fn new(rl: &RaylibHandle) -> Self {
+        View {
+            camera: Camera2D {
+                offset: Vector2::zero(),
+                target: Vector2::zero(),
+                rotation: 0.0,
+                zoom: dpi(rl),
+            },
+            size: window_size(rl),
+            zoom: 1.0,
+            dpi: dpi(rl),
+        }
+    }
+
+    /// Returns the previous `size` when the window has been resized.
+    pub fn update(&mut self, rl: &RaylibHandle) -> Option<Vector2> {
+        let mouse = rl.get_mouse_position();
+        let wheel = rl.get_mouse_wheel_move();
+        if wheel != 0.0 {
+            // keep the point under the cursor in place
+            self.camera.target = rl.get_screen_to_world2D(mouse, self.camera);
+            self.camera.offset = mouse;
+            self.zoom = (self.zoom * ZOOM_STEP.powf(wheel)).clamp(ZOOM_MIN, ZOOM_MAX);
+        }
+        if rl.is_mouse_button_down(MouseButton::MOUSE_BUTTON_MIDDLE) {
+            self.camera.target -= rl.get_mouse_delta() / self.camera.zoom;
+        }
+        if rl.is_key_pressed(KeyboardKey::KEY_HOME) {
+            self.camera.target = Vector2::zero();
+            self.camera.offset = Vector2::zero();
+            self.zoom = 1.0;
+        }
+        self.dpi = dpi(rl);
+        self.camera.zoom = self.dpi * self.zoom;
+
+        let size = window_size(rl);
+        if size != self.size {
+            Some(std::mem::replace(&mut self.size, size))
+        } else {
+            None
+        }
+    }
+
+    /// Cursor position in layout coordinates.
+    pub fn mouse(&self, rl: &RaylibHandle) -> Vector2 {
+        rl.get_screen_to_world2D(rl.get_mouse_position(), self.camera)
+    }
+
+    /// Camera for text drawn over the graph: DPI scaling, but no zoom or pan.
+    pub fn overlay(&self) -> Camera2D {
+        Camera2D {
+            offset: Vector2::zero(),
+            target: Vector2::zero(),
+            rotation: 0.0,
+            zoom: self.dpi,
+        }
+    }
+}
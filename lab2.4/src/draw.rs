@@ -2,19 +2,38 @@ use std::f32::consts::PI;
 
 use raylib::prelude::*;
 
+use crate::layout;
+
 const FONT_SIZE: i32 = 32;
+const LABEL_FONT_SIZE: i32 = 20;
 const CHAR_WIDTH: f32 = 0.27;
 
-const VERTEX_RADIUS: f32 = 20.0;
+const PANEL_FONT_SIZE: i32 = 20;
+const PANEL_PADDING: f32 = 8.0;
+
+pub const VERTEX_RADIUS: f32 = 20.0;
 
 const ARROWHEAD_LEN: f32 = VERTEX_RADIUS * 0.5;
 const ARROWHEAD_ANGLE: f32 = PI / 6.0;
 
-pub fn draw_text(d: &mut RaylibDrawHandle, font: &Font, text: &str, position: Vector2) {
+pub fn draw_text(d: &mut impl RaylibDraw, font: &Font, text: &str, position: Vector2) {
     d.draw_text_ex(font, text, position, FONT_SIZE as f32, 0.0, Color::BLACK);
 }
 
-pub fn draw_vertex(d: &mut RaylibDrawHandle, center: Vector2, weight: &str, font: &Font) {
+/// Small label centered on `position`, e.g. the multiplicity of parallel edges.
+pub fn draw_label(d: &mut impl RaylibDraw, font: &Font, text: &str, position: Vector2) {
+    let size = font.measure_text(text, LABEL_FONT_SIZE as f32, 0.0);
+    d.draw_text_ex(
+        font,
+        text,
+        position - size * 0.5,
+        LABEL_FONT_SIZE as f32,
+        0.0,
+        Color::RED,
+    );
+}
+
+pub fn draw_vertex(d: &mut impl RaylibDraw, center: Vector2, weight: &str, font: &Font) {
     d.draw_circle_lines(center.x as i32, center.y as i32, VERTEX_RADIUS, Color::BLUE);
     if !weight.is_empty() {
         let text_len = weight.chars().count();
@@ -32,7 +51,7 @@ pub fn draw_vertex(d: &mut RaylibDrawHandle, center: Vector2, weight: &str, font
     }
 }
 
-fn draw_arrowhead(d: &mut RaylibDrawHandle, position: Vector2, direction: Vector2) {
+fn draw_arrowhead(d: &mut impl RaylibDraw, position: Vector2, direction: Vector2) {
     d.draw_line_v(
         position,
         position - direction.rotated(ARROWHEAD_ANGLE) * ARROWHEAD_LEN,
@@ -46,7 +65,7 @@ fn draw_arrowhead(d: &mut RaylibDrawHandle, position: Vector2, direction: Vector
 }
 
 pub fn draw_straight_edge(
-    d: &mut RaylibDrawHandle,
+    d: &mut impl RaylibDraw,
     center_from: Vector2,
     center_to: Vector2,
     directional: bool,
@@ -61,31 +80,32 @@ pub fn draw_straight_edge(
     }
 }
 
-pub fn draw_angled_edge(
-    d: &mut RaylibDrawHandle,
+/// Quadratic Bézier edge, see `layout::edge_bend` for how `bend` is picked.
+pub fn draw_curved_edge(
+    d: &mut impl RaylibDraw,
     center_from: Vector2,
     center_to: Vector2,
+    bend: f32,
     directed: bool,
 ) {
-    const EDGE_BASE_ANGLE: f32 = 0.05 * PI;
-
-    let direction = (center_to - center_from).normalized();
-    let from = center_from + direction * VERTEX_RADIUS;
-    let to = center_to - direction * VERTEX_RADIUS;
+    let control = layout::bezier_control(center_from, center_to, bend);
+    let from = center_from + (control - center_from).normalized() * VERTEX_RADIUS;
+    let to = center_to + (control - center_to).normalized() * VERTEX_RADIUS;
 
-    let vector = to - from;
-    let vector_middle = vector * 0.5;
-    let mid_offset = vector_middle.length() * EDGE_BASE_ANGLE.tan();
-
-    let midpoint = from + vector_middle + direction.rotated(0.5 * PI) * mid_offset;
-    d.draw_line_v(from, midpoint, Color::BLACK);
-    d.draw_line_v(midpoint, to, Color::BLACK);
+    let points: [Vector2; layout::CURVE_SEGMENTS + 1] = std::array::from_fn(|i| {
+        layout::bezier_point(from, control, to, i as f32 / layout::CURVE_SEGMENTS as f32)
+    });
+    d.draw_line_strip(&points, Color::BLACK);
     if directed {
-        draw_arrowhead(d, to, (to - midpoint).normalized());
+        draw_arrowhead(
+            d,
+            to,
+            layout::bezier_tangent(from, control, to, 1.0).normalized(),
+        );
     }
 }
 
-pub fn draw_looping_edge(d: &mut RaylibDrawHandle, center: Vector2) {
+pub fn draw_looping_edge(d: &mut impl RaylibDraw, center: Vector2) {
     const POINTS: usize = 16;
     const RADIUS: f32 = 12.0;
     const START_ANGLE: f32 = -0.9 * PI;
@@ -117,3 +137,38 @@ pub fn draw_looping_edge(d: &mut RaylibDrawHandle, center: Vector2) {
         Color::BLACK,
     );
 }
+
+/// Size of the box `draw_panel` draws around `lines`.
+pub fn panel_size(font: &Font, lines: &[String]) -> Vector2 {
+    let width = lines
+        .iter()
+        .map(|line| font.measure_text(line, PANEL_FONT_SIZE as f32, 0.0).x)
+        .fold(0.0, f32::max);
+    Vector2 {
+        x: width + 2.0 * PANEL_PADDING,
+        y: lines.len() as f32 * PANEL_FONT_SIZE as f32 + 2.0 * PANEL_PADDING,
+    }
+}
+
+/// Lines of text on a light box with its top left corner at `position`.
+pub fn draw_panel(d: &mut impl RaylibDraw, font: &Font, lines: &[String], position: Vector2) {
+    d.draw_rectangle_v(
+        position,
+        panel_size(font, lines),
+        Color::LIGHTGRAY.alpha(0.9),
+    );
+    lines.iter().enumerate().for_each(|(i, line)| {
+        let offset = Vector2 {
+            x: PANEL_PADDING,
+            y: PANEL_PADDING + i as f32 * PANEL_FONT_SIZE as f32,
+        };
+        d.draw_text_ex(
+            font,
+            line,
+            position + offset,
+            PANEL_FONT_SIZE as f32,
+            0.0,
+            Color::BLACK,
+        );
+    });
+}
@@ -5,12 +5,7 @@ use std::{
     vec,
 };
 
-use rand::{Rng, SeedableRng, rngs::SmallRng};
-
-// $\mathtt{\overline{n_1 n_2 n_3 n_4} = 4228}$
-pub const DEFAULT_ROWS: &[usize] = &[4, 3, 5];
-pub const VERTEX_COUNT: usize = 12; // $\mathtt{10 + n_3} = 12$
-const RANDOM_SEED: u64 = 4228;
+use crate::rng::Xoshiro256;
 
 #[derive(Clone)]
 pub struct AdjMatrix(pub Vec<Vec<u32>>);
@@ -165,20 +160,29 @@ impl AdjMatrix {
 }
 
 impl AdjMatrix {
-    pub fn generate(k: f32) -> Self {
-        let mut rng = SmallRng::seed_from_u64(RANDOM_SEED);
+    pub fn generate(size: usize, seed: u64, k: f32) -> Self {
+        let mut rng = Xoshiro256::seed_from_u64(seed);
         let iter = std::iter::repeat_with(move || rng.random_range(0.0..2.0));
 
         AdjMatrix(
-            iter.take(VERTEX_COUNT * VERTEX_COUNT)
+            iter.take(size * size)
                 .map(|i| f32::min(i * k, 1.0) as u32)
                 .collect::<Vec<_>>()
-                .chunks(VERTEX_COUNT)
+                .chunks(size)
                 .map(|row| row.to_vec())
                 .collect(),
         )
     }
 
+    /// Every `(from, to)` pair with at least one edge, parallel edges of a
+    /// multigraph (entries > 1, e.g. after `Mul` or `Add`) only once.
+    pub fn edges(&self) -> Vec<(usize, usize)> {
+        (0..self.0.len())
+            .flat_map(|i| (0..self.0.len()).map(move |j| (i, j)))
+            .filter(|(i, j)| self.0[*i][*j] != 0)
+            .collect()
+    }
+
     pub fn undir(&self) -> Self {
         let mut undir_matrix = self.clone();
         for i in 0..self.0.len() {
@@ -229,12 +233,10 @@ impl AdjMatrix {
 
         for i in 0..self.0.len() {
             for j in 0..self.0.len() {
-                let mut remaining = squared.0[i][j];
-                if remaining > 0 {
+                if squared.0[i][j] > 0 {
                     for k in 0..self.0.len() {
                         if self.0[i][k] == 1 && self.0[k][j] == 1 {
                             paths.push((i, k, j).into());
-                            remaining -= 1;
                         }
                     }
                 }
@@ -250,13 +252,11 @@ impl AdjMatrix {
 
         for i in 0..self.0.len() {
             for j in 0..self.0.len() {
-                let mut remaining = cubed.0[i][j];
-                if remaining > 0 {
+                if cubed.0[i][j] > 0 {
                     for k in 0..self.0.len() {
                         for l in 0..self.0.len() {
                             if self.0[i][k] == 1 && self.0[k][l] == 1 && self.0[l][j] == 1 {
                                 paths.push((i, k, l, j).into());
-                                remaining -= 1;
                             }
                         }
                     }
@@ -324,3 +324,74 @@ impl AdjMatrix {
         AdjMatrix(cond_matrix)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variant::{Lab, Variant};
+
+    fn matrix(rows: &[[u32; 12]]) -> Vec<Vec<u32>> {
+        rows.iter().map(|row| row.to_vec()).collect()
+    }
+
+    // the matrices `main` prints for the default variant
+    #[test]
+    fn variant_4228() {
+        let variant = Variant::new(4228).unwrap();
+        let size = variant.vertex_count();
+        let dir_matrix = AdjMatrix::generate(size, variant.seed(), variant.k(Lab::Properties));
+        assert_eq!(
+            dir_matrix.0,
+            matrix(&[
+                [0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 1, 0],
+                [0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 1],
+                [0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0],
+                [0, 0, 1, 1, 0, 1, 0, 1, 0, 0, 0, 0],
+                [0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 1, 0],
+                [0, 0, 0, 0, 0, 1, 0, 1, 0, 0, 0, 0],
+                [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0],
+                [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+                [0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1, 0],
+                [1, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+                [0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1, 0],
+                [0, 0, 0, 1, 0, 1, 0, 0, 1, 0, 0, 0],
+            ])
+        );
+        assert_eq!(
+            dir_matrix.undir().0,
+            matrix(&[
+                [0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 1, 0],
+                [0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 1],
+                [1, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0],
+                [0, 1, 0, 1, 0, 1, 0, 1, 0, 0, 0, 0],
+                [0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 1, 0],
+                [0, 0, 1, 1, 0, 1, 0, 1, 0, 0, 0, 0],
+                [0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 1, 0],
+                [0, 0, 0, 1, 0, 1, 0, 0, 0, 0, 0, 0],
+                [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0],
+                [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+                [1, 0, 0, 0, 1, 0, 1, 0, 1, 0, 1, 0],
+                [0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            ])
+        );
+        let dir_matrix2 =
+            AdjMatrix::generate(size, variant.seed(), variant.k(Lab::PropertiesModified));
+        assert_eq!(
+            dir_matrix2.0,
+            matrix(&[
+                [0, 1, 1, 1, 0, 0, 1, 0, 0, 0, 1, 0],
+                [0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 1, 1],
+                [0, 0, 1, 0, 0, 1, 1, 0, 0, 0, 0, 0],
+                [0, 0, 1, 1, 0, 1, 0, 1, 1, 1, 0, 1],
+                [0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 1, 0],
+                [0, 0, 0, 0, 0, 1, 0, 1, 0, 1, 0, 0],
+                [0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 1, 0],
+                [0, 0, 1, 0, 0, 0, 0, 0, 0, 1, 0, 0],
+                [0, 1, 0, 0, 0, 1, 0, 0, 1, 0, 1, 1],
+                [1, 0, 1, 0, 0, 0, 1, 0, 0, 0, 0, 0],
+                [0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1, 1],
+                [1, 0, 1, 1, 0, 1, 1, 0, 1, 1, 0, 1],
+            ])
+        );
+    }
+}
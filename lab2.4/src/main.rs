@@ -1,62 +1,56 @@
 #![allow(clippy::needless_range_loop)]
 
-use graph::{AdjMatrix, DEFAULT_ROWS, VERTEX_COUNT};
+use graph::AdjMatrix;
+use layout::{Arrangement, Hover, VertexPos};
 use raylib::{color::Color, prelude::*};
+use variant::{Lab, Variant};
+use view::View;
 mod draw;
 mod graph;
+mod layout;
+mod rng;
+mod variant;
+mod view;
 
 const WIN_WIDTH: i32 = 800;
 const WIN_HEIGHT: i32 = 600;
 const WIN_MARGIN: f32 = 0.8;
+const TOOLTIP_OFFSET: Vector2 = Vector2 { x: 12.0, y: 12.0 };
 
-const K_1: f32 = 1.0 - 2.0 * 0.01 - 8.0 * 0.01 - 0.3;
-const K_2: f32 = 1.0 - 2.0 * 0.005 - 8.0 * 0.005 - 0.27;
-
-#[derive(Debug, Clone, Copy)]
-struct VertexPos {
-    v: Vector2,
-    row: usize,
-    col: usize,
-}
-
-fn draw_all_vertices(d: &mut RaylibDrawHandle, font: &Font, rows: &[usize]) -> Vec<VertexPos> {
-    fn current_position(index: usize, rows: &[usize]) -> (usize, usize) {
-        let mut cumulative = 0;
-        for (row, &count) in rows.iter().enumerate() {
-            if index < cumulative + count {
-                return (row, index - cumulative);
-            }
-            cumulative += count;
-        }
-        (usize::MAX, usize::MAX)
-    }
-    let winwidth = WIN_WIDTH as f32 * WIN_MARGIN;
-    let winheight = WIN_HEIGHT as f32 * WIN_MARGIN;
-    let vertex_count = rows.iter().sum();
-    let vertex_coords: Vec<VertexPos> = Vec::from_iter((0..vertex_count).map(|i| {
-        let (row, col) = current_position(i, rows);
-
-        let x_offset = (WIN_WIDTH as f32 - winwidth) * 0.5;
-        let y_offset = (WIN_HEIGHT as f32 - winheight) * 0.5;
-        VertexPos {
-            v: Vector2 {
-                x: (winwidth / (DEFAULT_ROWS[row] - 1) as f32 * col as f32) + x_offset,
-                y: (winheight / (DEFAULT_ROWS.len() - 1) as f32 * row as f32) + y_offset,
-            },
-            row,
-            col,
-        }
-    }));
-
-    (0..vertex_count).for_each(|i| {
+fn draw_all_vertices(d: &mut impl RaylibDraw, font: &Font, vertex_coords: &[VertexPos]) {
+    (0..vertex_coords.len()).for_each(|i| {
         draw::draw_vertex(d, vertex_coords[i].v, &((i + 1).to_string()), font);
     });
+}
+
+/// Gap between neighbouring curves of parallel edges.
+const PARALLEL_SPACING: f32 = 0.75 * draw::VERTEX_RADIUS;
+/// Parallel edges drawn between one pair of vertices, the rest are only counted
+/// by the label.
+const MAX_PARALLEL: u32 = 5;
 
-    vertex_coords
+/// Bends of `count` parallel edges fanned out from the routed `base` bend:
+/// further away from the chord if the base is already curved, otherwise
+/// alternating sides around the straight line.
+fn parallel_bends(base: f32, count: u32) -> Vec<f32> {
+    (0..count.min(MAX_PARALLEL))
+        .map(|m| {
+            if base != 0.0 {
+                base + base.signum() * m as f32 * PARALLEL_SPACING
+            } else if m % 2 == 1 {
+                m.div_ceil(2) as f32 * PARALLEL_SPACING
+            } else {
+                -((m / 2) as f32) * PARALLEL_SPACING
+            }
+        })
+        .collect()
 }
 
+/// Entries above 1 (multigraphs, e.g. powers of the matrix) are drawn as a fan
+/// of parallel curves labelled with their multiplicity.
 fn draw_all_edges(
-    d: &mut RaylibDrawHandle,
+    d: &mut impl RaylibDraw,
+    font: &Font,
     adj_matrix: &AdjMatrix,
     vertex_coords: &[VertexPos],
     directed: bool,
@@ -66,33 +60,56 @@ fn draw_all_edges(
         for j in lower..vertex_coords.len() {
             let origin = vertex_coords[i];
             let destination = vertex_coords[j];
-            let row_absdiff = (destination.row as i64 - origin.row as i64).abs();
-            let col_absdiff = (destination.col as i64 - origin.col as i64).abs();
-
-            if adj_matrix.0[i][j] == 1 {
-                if i == j {
-                    draw::draw_looping_edge(d, origin.v);
-                } else if (adj_matrix.0[j][i] == 1 && directed) // symmetric
-                    || (row_absdiff == 0 && col_absdiff > 1) // same row, goes through others
-                    || (col_absdiff == 0 && row_absdiff > 1) // same col, goes through others
-                    || (origin.v.x == destination.v.x) // same x coordinate, yes, still possible
-                    || col_absdiff >= 3
-                // honestly ^ whatever this is
-                {
-                    draw::draw_angled_edge(d, origin.v, destination.v, directed);
-                } else {
+            let count = adj_matrix.0[i][j];
+
+            if count == 0 {
+                continue;
+            }
+            if i == j {
+                draw::draw_looping_edge(d, origin.v);
+                if count > 1 {
+                    let label_pos = origin.v
+                        + Vector2 {
+                            x: 1.6 * draw::VERTEX_RADIUS,
+                            y: -1.9 * draw::VERTEX_RADIUS,
+                        };
+                    draw::draw_label(d, font, &count.to_string(), label_pos);
+                }
+                continue;
+            }
+
+            let min_bend = if directed && adj_matrix.0[j][i] != 0 {
+                layout::ANTIPARALLEL_BEND
+            } else {
+                0.0
+            };
+            let bends = parallel_bends(layout::edge_bend(vertex_coords, i, j, min_bend), count);
+            for &bend in &bends {
+                if bend == 0.0 {
                     draw::draw_straight_edge(d, origin.v, destination.v, directed);
+                } else {
+                    draw::draw_curved_edge(d, origin.v, destination.v, bend, directed);
                 }
             }
+            if count > 1 {
+                // just outside the middle of the outermost curve
+                let outer = bends[bends.len() - 1];
+                let outer = outer + outer.signum() * 0.5 * PARALLEL_SPACING;
+                let control = layout::bezier_control(origin.v, destination.v, outer);
+                let label_pos = layout::bezier_point(origin.v, control, destination.v, 0.5);
+                draw::draw_label(d, font, &count.to_string(), label_pos);
+            }
         }
     }
 }
 
-fn generate_and_print() -> (AdjMatrix, AdjMatrix, AdjMatrix, AdjMatrix) {
-    let dir_matrix = AdjMatrix::generate(K_1);
-    let in_degrees = (0..VERTEX_COUNT).map(|i| dir_matrix.degree_in(i));
-    let out_degrees = (0..VERTEX_COUNT).map(|i| dir_matrix.degree_out(i));
+fn generate_and_print(variant: &Variant) -> (AdjMatrix, AdjMatrix, AdjMatrix, AdjMatrix) {
+    let vertex_count = variant.vertex_count();
+    let dir_matrix = AdjMatrix::generate(vertex_count, variant.seed(), variant.k(Lab::Properties));
+    let in_degrees = (0..vertex_count).map(|i| dir_matrix.degree_in(i));
+    let out_degrees = (0..vertex_count).map(|i| dir_matrix.degree_out(i));
 
+    println!("Variant: {}", variant);
     println!("Directed adjacency matrix:\n{}", dir_matrix);
     print!("Vertex semi-degrees (IN):  ");
     in_degrees.for_each(|deg| print!("{} ", deg));
@@ -104,9 +121,13 @@ fn generate_and_print() -> (AdjMatrix, AdjMatrix, AdjMatrix, AdjMatrix) {
     println!("\n\n\nUndirected adjacency matrix:\n{}", undir_matrix);
     println!("{:#}", undir_matrix);
 
-    let dir_matrix2 = AdjMatrix::generate(K_2);
-    let in_degrees2 = (0..VERTEX_COUNT).map(|i| dir_matrix2.degree_in(i));
-    let out_degrees2 = (0..VERTEX_COUNT).map(|i| dir_matrix2.degree_out(i));
+    let dir_matrix2 = AdjMatrix::generate(
+        vertex_count,
+        variant.seed(),
+        variant.k(Lab::PropertiesModified),
+    );
+    let in_degrees2 = (0..vertex_count).map(|i| dir_matrix2.degree_in(i));
+    let out_degrees2 = (0..vertex_count).map(|i| dir_matrix2.degree_out(i));
     let dir_paths2: Vec<_> = dir_matrix2
         .all_paths_of_2()
         .iter()
@@ -146,65 +167,174 @@ fn generate_and_print() -> (AdjMatrix, AdjMatrix, AdjMatrix, AdjMatrix) {
     (dir_matrix, undir_matrix, dir_matrix2, condensed)
 }
 
+/// Tooltip title first, then the details for the side panel.
+fn inspect(adj_matrix: &AdjMatrix, directed: bool, hover: Hover) -> Vec<String> {
+    match hover {
+        Hover::Vertex(vertex) => {
+            let component = adj_matrix
+                .conn_components()
+                .iter()
+                .position(|comp| comp.contains(&vertex))
+                .unwrap_or(0);
+            let mut lines = vec![format!("Vertex {}", vertex + 1)];
+            if directed {
+                lines.push(format!("In: {}", adj_matrix.degree_in(vertex)));
+                lines.push(format!("Out: {}", adj_matrix.degree_out(vertex)));
+            } else {
+                lines.push(format!("Degree: {}", adj_matrix.degree_out(vertex)));
+            }
+            lines.push(format!("Component: {}", component + 1));
+            lines
+        }
+        Hover::Edge(from, to) => {
+            let mut lines = vec![format!(
+                "Edge {} {} {}",
+                from + 1,
+                if directed { "->" } else { "--" },
+                to + 1
+            )];
+            if adj_matrix.0[from][to] > 1 {
+                lines.push(format!("Multiplicity: {}", adj_matrix.0[from][to]));
+            }
+            lines
+        }
+    }
+}
+
 fn main() {
+    let variant = Variant::from_args();
+    let rows = variant.rows();
+
     let (mut rl, thread) = raylib::init()
         .size(WIN_WIDTH, WIN_HEIGHT)
         .log_level(TraceLogLevel::LOG_WARNING)
         .title("ASD Lab 2.4")
+        .resizable()
         .build();
+    rl.set_window_min_size(WIN_WIDTH / 2, WIN_HEIGHT / 2);
 
     let font = rl.load_font(&thread, "FiraCode-Regular.ttf").unwrap();
 
     let mut state: KeyboardKey = KeyboardKey::KEY_F1;
 
-    let (dir_matrix, undir_matrix, dir_matrix2, condensed) = generate_and_print();
+    let (dir_matrix, undir_matrix, dir_matrix2, condensed) = generate_and_print(&variant);
+    // walks of length 2 and 3 as multigraphs
+    let squared = &dir_matrix2 * &dir_matrix2;
+    let cubed = &squared * &dir_matrix2;
+    println!("\nWalks of 2 (A^2):\n{}", squared);
+    println!("Walks of 3 (A^3):\n{}", cubed);
+
+    let layout_file = layout::file_name(&variant, None);
+    let condensed_file = layout::file_name(&variant, Some("condensed"));
+    let mut view = View::new(&rl);
+    let mut vertex_coords = layout::load_or_rows(&layout_file, &rows, view.size);
+    let condensed_rows = vec![condensed.0.len()];
+    let mut condensed_coords = layout::load_or_rows(&condensed_file, &condensed_rows, view.size);
+    let mut drag = layout::Drag::new();
+    let mut arrangement = Arrangement::Rows;
 
     while !rl.window_should_close() {
         let pressed = rl.get_key_pressed();
+        if let Some(old_size) = view.update(&rl) {
+            layout::rescale(&mut vertex_coords, old_size, view.size);
+            layout::rescale(&mut condensed_coords, old_size, view.size);
+        }
+        let (coords, file, matrix, view_rows) = match state {
+            KeyboardKey::KEY_F4 => (
+                &mut condensed_coords,
+                &condensed_file,
+                &condensed,
+                &condensed_rows,
+            ),
+            KeyboardKey::KEY_F2 => (&mut vertex_coords, &layout_file, &undir_matrix, &rows),
+            KeyboardKey::KEY_F3 => (&mut vertex_coords, &layout_file, &dir_matrix2, &rows),
+            KeyboardKey::KEY_F7 => (&mut vertex_coords, &layout_file, &squared, &rows),
+            KeyboardKey::KEY_F8 => (&mut vertex_coords, &layout_file, &cubed, &rows),
+            _ => (&mut vertex_coords, &layout_file, &dir_matrix, &rows),
+        };
+        drag.update(&rl, view.mouse(&rl), coords);
+        if pressed == Some(KeyboardKey::KEY_F5) {
+            match layout::save(file, coords) {
+                Ok(()) => println!("Layout saved to {}", file),
+                Err(err) => eprintln!("Could not save {}: {}", file, err),
+            }
+        } else if pressed == Some(KeyboardKey::KEY_F6) {
+            arrangement = arrangement.next();
+            println!("Arrangement: {:?}", arrangement);
+            *coords = layout::arrange(arrangement, view_rows, &matrix.edges(), &[], view.size);
+        }
+        let directed = state != KeyboardKey::KEY_F2;
+        let mouse = view.mouse(&rl);
+        let inspection = layout::hover(coords, &matrix.edges(), directed, mouse)
+            .map(|hover| inspect(matrix, directed, hover));
         let mut d = rl.begin_drawing(&thread);
 
         d.clear_background(Color::WHITE);
-        if let Some(key) = pressed {
-            if [
+        if let Some(key) = pressed
+            && [
                 KeyboardKey::KEY_F1,
                 KeyboardKey::KEY_F2,
                 KeyboardKey::KEY_F3,
                 KeyboardKey::KEY_F4,
+                KeyboardKey::KEY_F7,
+                KeyboardKey::KEY_F8,
             ]
             .contains(&key)
-            {
-                state = key;
+        {
+            state = key;
+        }
+
+        {
+            let mut d = d.begin_mode2D(view.camera);
+            match state {
+                KeyboardKey::KEY_F1 => {
+                    draw_all_vertices(&mut d, &font, &vertex_coords);
+                    draw_all_edges(&mut d, &font, &dir_matrix, &vertex_coords, true);
+                }
+                KeyboardKey::KEY_F2 => {
+                    draw_all_vertices(&mut d, &font, &vertex_coords);
+                    draw_all_edges(&mut d, &font, &undir_matrix, &vertex_coords, false);
+                }
+                KeyboardKey::KEY_F3 => {
+                    draw_all_vertices(&mut d, &font, &vertex_coords);
+                    draw_all_edges(&mut d, &font, &dir_matrix2, &vertex_coords, true);
+                }
+                KeyboardKey::KEY_F4 => {
+                    draw_all_vertices(&mut d, &font, &condensed_coords);
+                    draw_all_edges(&mut d, &font, &condensed, &condensed_coords, true);
+                }
+                KeyboardKey::KEY_F7 => {
+                    draw_all_vertices(&mut d, &font, &vertex_coords);
+                    draw_all_edges(&mut d, &font, &squared, &vertex_coords, true);
+                }
+                KeyboardKey::KEY_F8 => {
+                    draw_all_vertices(&mut d, &font, &vertex_coords);
+                    draw_all_edges(&mut d, &font, &cubed, &vertex_coords, true);
+                }
+                _ => {}
+            }
+            if let Some(lines) = &inspection {
+                draw::draw_panel(&mut d, &font, &lines[..1], mouse + TOOLTIP_OFFSET);
             }
         }
 
+        let mut d = d.begin_mode2D(view.overlay());
         draw::draw_text(
             &mut d,
             &font,
-            "<F1>    <F2>    <F3>    <F4>",
+            "<F1> <F2> <F3> <F4> <F7> A^2 <F8> A^3",
             Vector2 {
-                x: 0.2 * WIN_WIDTH as f32,
-                y: 0.01 * WIN_HEIGHT as f32,
+                x: 0.02 * view.size.x,
+                y: 0.01 * view.size.y,
             },
         );
-
-        match state {
-            KeyboardKey::KEY_F1 => {
-                let vertex_coords = draw_all_vertices(&mut d, &font, DEFAULT_ROWS);
-                draw_all_edges(&mut d, &dir_matrix, &vertex_coords, true);
-            }
-            KeyboardKey::KEY_F2 => {
-                let vertex_coords = draw_all_vertices(&mut d, &font, DEFAULT_ROWS);
-                draw_all_edges(&mut d, &undir_matrix, &vertex_coords, false);
-            }
-            KeyboardKey::KEY_F3 => {
-                let vertex_coords = draw_all_vertices(&mut d, &font, DEFAULT_ROWS);
-                draw_all_edges(&mut d, &dir_matrix2, &vertex_coords, true);
-            }
-            KeyboardKey::KEY_F4 => {
-                let vertex_coords = draw_all_vertices(&mut d, &font, &[condensed.0.len()]);
-                draw_all_edges(&mut d, &condensed, &vertex_coords, true);
-            }
-            _ => {}
+        if let Some(lines) = &inspection {
+            let panel = draw::panel_size(&font, lines);
+            let position = Vector2 {
+                x: 0.99 * view.size.x - panel.x,
+                y: 0.08 * view.size.y,
+            };
+            draw::draw_panel(&mut d, &font, lines, position);
         }
     }
 }
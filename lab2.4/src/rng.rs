@@ -0,0 +1,74 @@
+use std::ops::Range;
+
+// xoshiro256++ seeded with SplitMix64: the exact generator behind `rand` 0.9's
+// `SmallRng` on 64-bit targets, pinned here so the variant matrices never depend
+// on which `rand` version (or platform) the lab is built with.
+// https://prng.di.unimi.it/xoshiro256plusplus.c
+#[derive(Clone)]
+pub struct Xoshiro256 {
+    s: [u64; 4],
+}
+
+impl Xoshiro256 {
+    pub fn seed_from_u64(mut state: u64) -> Self {
+        const PHI: u64 = 0x9e3779b97f4a7c15;
+        let mut s = [0; 4];
+        for i in s.iter_mut() {
+            state = state.wrapping_add(PHI);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+            *i = z ^ (z >> 31);
+        }
+        Xoshiro256 { s }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let result = self.s[0]
+            .wrapping_add(self.s[3])
+            .rotate_left(23)
+            .wrapping_add(self.s[0]);
+        let t = self.s[1] << 17;
+
+        self.s[2] ^= self.s[0];
+        self.s[3] ^= self.s[1];
+        self.s[1] ^= self.s[2];
+        self.s[0] ^= self.s[3];
+        self.s[2] ^= t;
+        self.s[3] = self.s[3].rotate_left(45);
+
+        result
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    // Same as `rand::Rng::random_range` for `f32`: the top 23 bits become the
+    // mantissa of a float in [1, 2), which is then shifted and scaled.
+    pub fn random_range(&mut self, range: Range<f32>) -> f32 {
+        let value1_2 = f32::from_bits((127 << 23) | (self.next_u32() >> 9));
+        (value1_2 - 1.0) * (range.end - range.start) + range.start
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // first outputs of rand 0.9's `SmallRng::seed_from_u64(4228)`
+    #[test]
+    fn matches_small_rng() {
+        let mut rng = Xoshiro256::seed_from_u64(4228);
+        let outputs: Vec<u64> = (0..4).map(|_| rng.next_u64()).collect();
+        assert_eq!(
+            outputs,
+            [
+                0x5a0e748bcf5b5f54,
+                0xd3dc287be0f88e12,
+                0xfb7d9261b6d46ff9,
+                0xbca22359ff1d207f,
+            ]
+        );
+    }
+}
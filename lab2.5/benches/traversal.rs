@@ -0,0 +1,79 @@
+//! Runs BFS and DFS to the end on sparse random graphs of growing size, and
+//! on a chain as deep as it is long; the time per vertex and edge staying
+//! flat shows a traversal is linear.
+//!
+//! `cargo bench`
+
+// the parts of the modules only the window uses, and the imports of their
+// unit tests, which a bench without the test harness leaves out
+#![allow(dead_code, unused_imports)]
+
+#[path = "../src/graph.rs"]
+mod graph;
+#[path = "../src/rng.rs"]
+mod rng;
+#[path = "../src/variant.rs"]
+mod variant;
+
+use std::time::Instant;
+
+use graph::{AdjList, Bfs, Costs, Dfs, Search, SearchStep};
+use rng::Xoshiro256;
+
+const SIZES: [usize; 3] = [25_000, 50_000, 100_000];
+const DEGREE: usize = 3;
+
+/// `DEGREE` edges out of every vertex, to random vertices.
+fn sparse(size: usize, seed: u64) -> AdjList {
+    let mut rng = Xoshiro256::seed_from_u64(seed);
+    let edges: Vec<(usize, usize)> = (0..size * DEGREE)
+        .map(|i| (i / DEGREE, (rng.next_u64() % size as u64) as usize))
+        .collect();
+    AdjList::new(size, &edges)
+}
+
+/// Every vertex to the next one and back to the first, so the search tree
+/// is one long path and every back edge spans all of it.
+fn chain(size: usize) -> AdjList {
+    let edges: Vec<(usize, usize)> = (1..size)
+        .flat_map(|vertex| [(vertex - 1, vertex), (vertex, 0)])
+        .collect();
+    AdjList::new(size, &edges)
+}
+
+fn run<S: Search>(graph: &AdjList, size: usize, edges: usize) {
+    let unit = |_: usize, _: usize| 1.0;
+    let costs = Costs {
+        length: &unit,
+        heuristic: &unit,
+    };
+    let started = Instant::now();
+    let mut step = SearchStep::<S>::new(0, size);
+    let mut steps = 0;
+    while graph.search_next(&mut step, &costs) {
+        steps += 1;
+    }
+    let elapsed = started.elapsed();
+    println!(
+        "{} {} vertices, {} edges: {:.2?} in {} steps, {:.1} ns per vertex and edge",
+        S::NAME,
+        size,
+        edges,
+        elapsed,
+        steps,
+        elapsed.as_nanos() as f64 / (size + edges) as f64
+    );
+}
+
+fn main() {
+    for size in SIZES {
+        let graph = sparse(size, size as u64);
+        run::<Bfs>(&graph, size, size * DEGREE);
+        run::<Dfs>(&graph, size, size * DEGREE);
+    }
+    for size in SIZES {
+        let graph = chain(size);
+        run::<Bfs>(&graph, size, 2 * (size - 1));
+        run::<Dfs>(&graph, size, 2 * (size - 1));
+    }
+}
@@ -1,15 +1,11 @@
 use std::{
     collections::VecDeque,
     fmt::{Debug, Display},
+    fs, io,
     marker::PhantomData,
 };
 
-use rand::{Rng, SeedableRng, rngs::SmallRng};
-
-// $\mathtt{\overline{n_1 n_2 n_3 n_4} = 4228}$
-pub const DEFAULT_ROWS: &[usize] = &[4, 3, 5];
-pub const VERTEX_COUNT: usize = 12; // $\mathtt{10 + n_3} = 12$
-const RANDOM_SEED: u64 = 4228;
+use crate::rng::Xoshiro256;
 
 #[derive(Clone)]
 pub struct AdjMatrix(pub Vec<Vec<u32>>);
@@ -30,117 +26,1036 @@ impl Display for AdjMatrix {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Bfs;
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Dfs;
 
-pub trait Search {}
-impl Search for Bfs {}
-impl Search for Dfs {}
+/// Edge lengths and the estimate of the length left from a vertex to the
+/// goal, for the searches that weigh their frontier.
+#[derive(Clone, Copy)]
+pub struct Costs<'a> {
+    pub length: &'a dyn Fn(usize, usize) -> f32,
+    pub heuristic: &'a dyn Fn(usize, usize) -> f32,
+}
+
+/// Order a search takes the neighbours of a vertex in, and the roots it
+/// moves on to once it runs out of them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Order {
+    Index,
+    Reverse,
+    /// Shortest edge first, then by index.
+    Weight,
+    /// Shuffled, the same way for the same seed and vertex.
+    Random(u64),
+}
+
+impl Order {
+    pub fn next(self, seed: u64) -> Self {
+        match self {
+            Order::Index => Order::Reverse,
+            Order::Reverse => Order::Weight,
+            Order::Weight => Order::Random(seed),
+            Order::Random(_) => Order::Index,
+        }
+    }
+
+    /// Sorts `vertices`, given in index order, as seen from `from`.
+    fn sort(self, from: usize, vertices: &mut [usize], costs: &Costs) {
+        match self {
+            Order::Index => {}
+            Order::Reverse => vertices.reverse(),
+            Order::Weight => vertices
+                .sort_by(|a, b| (costs.length)(from, *a).total_cmp(&(costs.length)(from, *b))),
+            Order::Random(seed) => {
+                let mut rng = Xoshiro256::seed_from_u64(seed ^ from as u64);
+                for i in (1..vertices.len()).rev() {
+                    vertices.swap(i, (rng.next_u64() % (i as u64 + 1)) as usize);
+                }
+            }
+        }
+    }
+}
+
+impl Display for Order {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Order::Index => write!(f, "index"),
+            Order::Reverse => write!(f, "reverse"),
+            Order::Weight => write!(f, "weight"),
+            Order::Random(seed) => write!(f, "random {}", seed),
+        }
+    }
+}
+
+pub trait Search: Sized {
+    const NAME: &'static str;
+
+    /// Starts a new search tree at `root`.
+    fn start(step: &mut SearchStep<Self>, root: usize);
+
+    /// Moves the search one step on, returns `false` once every vertex has
+    /// been finished or the goal has been found.
+    fn next(graph: &AdjList, step: &mut SearchStep<Self>, costs: &Costs) -> bool;
+}
+
+impl Search for Bfs {
+    const NAME: &'static str = "BFS";
+
+    fn start(step: &mut SearchStep<Self>, root: usize) {
+        step.discover(root, root);
+        step.queue.push_back(root);
+    }
+
+    /// Takes the oldest vertex off the queue and examines all of its edges.
+    fn next(graph: &AdjList, step: &mut SearchStep<Self>, costs: &Costs) -> bool {
+        if step.queue.is_empty() {
+            match step.next_root(costs) {
+                Some(root) => Self::start(step, root),
+                None => return false,
+            }
+        }
+        let Some(next) = step.queue.pop_front() else {
+            return false;
+        };
+        step.active = next;
+        step.tree.push((step.parent[next].unwrap_or(next), next));
+        // the first vertex a tree gives up is its root
+        if step.span[next].is_none() {
+            step.index_tree(graph, next, costs);
+        }
+        for to in step.neighbours(graph, next, costs) {
+            step.events.push(Event::ExamineEdge(next, to));
+            if step.discovered[to].is_none() {
+                step.discover(next, to);
+                step.queue.push_back(to);
+            } else if step.is_ancestor(to, next) {
+                step.events.push(Event::BackEdge(next, to));
+            } else if step.is_ancestor(next, to) {
+                step.events.push(Event::ForwardEdge(next, to));
+            } else {
+                step.events.push(Event::CrossEdge(next, to));
+            }
+        }
+        step.finish(next);
+        true
+    }
+}
+
+impl Search for Dfs {
+    const NAME: &'static str = "DFS";
+
+    fn start(step: &mut SearchStep<Self>, root: usize) {
+        step.discover(root, root);
+        step.tree.push((root, root));
+        step.stack.push((root, None));
+        step.active = root;
+    }
+
+    /// Goes on with the neighbours of the vertex on top of the stack until
+    /// one of them is discovered, or finishes the vertex when none is left.
+    fn next(graph: &AdjList, step: &mut SearchStep<Self>, costs: &Costs) -> bool {
+        loop {
+            let Some(&(vertex, _)) = step.stack.last() else {
+                return match step.next_root(costs) {
+                    Some(root) => {
+                        Self::start(step, root);
+                        true
+                    }
+                    None => false,
+                };
+            };
+            step.active = vertex;
+            let Some(to) = step.next_neighbour(graph, costs) else {
+                step.stack.pop();
+                step.finish(vertex);
+                return true;
+            };
+            step.events.push(Event::ExamineEdge(vertex, to));
+            // an edge to a vertex still on the stack closes a cycle, one to
+            // a finished vertex discovered later skips over tree edges
+            match (step.discovered[to], step.finished[to]) {
+                (None, _) => {
+                    step.discover(vertex, to);
+                    step.tree.push((vertex, to));
+                    step.stack.push((to, None));
+                    step.active = to;
+                    return true;
+                }
+                (Some(_), None) => step.events.push(Event::BackEdge(vertex, to)),
+                (Some(time), Some(_)) if Some(time) > step.discovered[vertex] => {
+                    step.events.push(Event::ForwardEdge(vertex, to))
+                }
+                _ => step.events.push(Event::CrossEdge(vertex, to)),
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Iddfs;
+#[derive(Debug, Clone, PartialEq)]
+pub struct UniformCost;
+#[derive(Debug, Clone, PartialEq)]
+pub struct Greedy;
+#[derive(Debug, Clone, PartialEq)]
+pub struct AStar;
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bidirectional;
+
+impl Search for Iddfs {
+    const NAME: &'static str = "IDDFS";
+
+    fn start(step: &mut SearchStep<Self>, root: usize) {
+        step.discover(root, root);
+        step.tree.push((root, root));
+        step.stack.push((root, None));
+        step.cost[root] = 0.0;
+        step.active = root;
+        step.found = step.goal == Some(root);
+    }
+
+    /// Depth-limited DFS that goes deeper by one each time it runs out of
+    /// vertices above the limit. A vertex is entered again when it is
+    /// reached at a smaller depth, so the limit is never too shallow.
+    fn next(graph: &AdjList, step: &mut SearchStep<Self>, costs: &Costs) -> bool {
+        if step.found {
+            return false;
+        }
+        loop {
+            let Some(&(vertex, _)) = step.stack.last() else {
+                // only a vertex at the limit can have an edge out of the
+                // vertices this round reached, going deeper finds nothing new
+                // otherwise
+                let cut_off = (0..step.discovered.len())
+                    .filter(|vertex| step.discovered[*vertex].is_some())
+                    .any(|vertex| {
+                        graph
+                            .successors(vertex)
+                            .iter()
+                            .any(|to| step.discovered[*to].is_none())
+                    });
+                if !cut_off {
+                    return false;
+                }
+                step.limit += 1;
+                step.events.push(Event::Deepen(step.limit));
+                step.restart();
+                Self::start(step, step.root);
+                return true;
+            };
+            step.active = vertex;
+            let depth = step.stack.len() - 1;
+            let to = if depth < step.limit {
+                step.next_neighbour(graph, costs)
+            } else {
+                None
+            };
+            let Some(to) = to else {
+                step.stack.pop();
+                step.finish(vertex);
+                return true;
+            };
+            step.events.push(Event::ExamineEdge(vertex, to));
+            if (depth + 1) as f32 >= step.cost[to] {
+                continue;
+            }
+            if step.discovered[to].is_none() {
+                step.discover(vertex, to);
+            } else {
+                step.reparent(vertex, to);
+                step.tree.retain(|(_, child)| *child != to);
+                step.events.push(Event::TreeEdge(vertex, to));
+            }
+            step.cost[to] = (depth + 1) as f32;
+            step.tree.push((vertex, to));
+            step.stack.push((to, None));
+            step.active = to;
+            step.found = step.goal == Some(to);
+            return true;
+        }
+    }
+}
+
+/// Takes the vertex with the lowest `priority` out of the open set and
+/// relaxes its edges. Tree edges are only reported once a vertex is taken,
+/// because a cheaper parent may still turn up before that.
+fn best_first<S: Search>(
+    graph: &AdjList,
+    step: &mut SearchStep<S>,
+    costs: &Costs,
+    priority: impl Fn(f32, f32) -> f32,
+) -> bool {
+    if step.found {
+        return false;
+    }
+    let estimate = |vertex: usize| {
+        step.goal
+            .map_or(0.0, |goal| (costs.heuristic)(vertex, goal))
+    };
+    let Some(index) = (0..step.queue.len()).min_by(|a, b| {
+        let a = step.queue[*a];
+        let b = step.queue[*b];
+        priority(step.cost[a], estimate(a)).total_cmp(&priority(step.cost[b], estimate(b)))
+    }) else {
+        return false;
+    };
+    let Some(next) = step.queue.remove(index) else {
+        return false;
+    };
+    step.active = next;
+    let parent = step.parent[next].unwrap_or(next);
+    step.tree.push((parent, next));
+    if parent != next {
+        step.events.push(Event::TreeEdge(parent, next));
+    }
+    if step.goal == Some(next) {
+        step.found = true;
+        step.finish(next);
+        return true;
+    }
+    for to in step.neighbours(graph, next, costs) {
+        if step.finished[to].is_some() {
+            continue;
+        }
+        step.events.push(Event::ExamineEdge(next, to));
+        let cost = step.cost[next] + (costs.length)(next, to);
+        if step.discovered[to].is_none() {
+            step.reach(next, to);
+            step.queue.push_back(to);
+        } else if cost < step.cost[to] {
+            step.reparent(next, to);
+        } else {
+            continue;
+        }
+        step.cost[to] = cost;
+    }
+    step.finish(next);
+    true
+}
+
+/// Starts a best-first search, which never moves on to other roots.
+fn best_first_start<S: Search>(step: &mut SearchStep<S>, root: usize) {
+    step.events.push(Event::NewRoot(root));
+    step.reach(root, root);
+    step.cost[root] = 0.0;
+    step.queue.push_back(root);
+}
+
+impl Search for UniformCost {
+    const NAME: &'static str = "UCS";
+
+    fn start(step: &mut SearchStep<Self>, root: usize) {
+        best_first_start(step, root);
+    }
+
+    fn next(graph: &AdjList, step: &mut SearchStep<Self>, costs: &Costs) -> bool {
+        best_first(graph, step, costs, |cost, _| cost)
+    }
+}
+
+impl Search for Greedy {
+    const NAME: &'static str = "Greedy";
+
+    fn start(step: &mut SearchStep<Self>, root: usize) {
+        best_first_start(step, root);
+    }
+
+    fn next(graph: &AdjList, step: &mut SearchStep<Self>, costs: &Costs) -> bool {
+        best_first(graph, step, costs, |_, estimate| estimate)
+    }
+}
+
+impl Search for AStar {
+    const NAME: &'static str = "A*";
+
+    fn start(step: &mut SearchStep<Self>, root: usize) {
+        best_first_start(step, root);
+    }
+
+    fn next(graph: &AdjList, step: &mut SearchStep<Self>, costs: &Costs) -> bool {
+        best_first(graph, step, costs, |cost, estimate| cost + estimate)
+    }
+}
+
+impl Search for Bidirectional {
+    const NAME: &'static str = "Bidirectional BFS";
 
-#[derive(Debug)]
+    /// Starts one BFS at `root` and another one at the goal, walking the
+    /// edges backwards.
+    fn start(step: &mut SearchStep<Self>, root: usize) {
+        step.discover(root, root);
+        step.queue.push_back(root);
+        match step.goal {
+            Some(goal) if goal == root => step.found = true,
+            Some(goal) => {
+                step.discover(goal, goal);
+                step.from_goal[goal] = true;
+                step.back_queue.push_back(goal);
+            }
+            None => {}
+        }
+    }
+
+    /// Expands a vertex from the smaller of the two frontiers, until a
+    /// vertex one side reaches has already been reached by the other.
+    fn next(graph: &AdjList, step: &mut SearchStep<Self>, costs: &Costs) -> bool {
+        if step.found {
+            return false;
+        }
+        let forward = step.back_queue.is_empty()
+            || (!step.queue.is_empty() && step.queue.len() <= step.back_queue.len());
+        let next = if forward {
+            step.queue.pop_front()
+        } else {
+            step.back_queue.pop_front()
+        };
+        let Some(next) = next else {
+            return false;
+        };
+        step.active = next;
+        step.tree.push((step.parent[next].unwrap_or(next), next));
+        let others = if forward {
+            step.neighbours(graph, next, costs)
+        } else {
+            step.sorted(next, graph.predecessors(next), costs)
+        };
+        for other in others {
+            let (from, to) = if forward {
+                (next, other)
+            } else {
+                (other, next)
+            };
+            step.events.push(Event::ExamineEdge(from, to));
+            if step.discovered[other].is_some() && step.from_goal[other] != forward {
+                continue;
+            }
+            step.events.push(Event::TreeEdge(from, to));
+            if step.discovered[other].is_some() {
+                step.found = true;
+                step.finish(next);
+                return true;
+            }
+            step.reach(next, other);
+            if forward {
+                step.queue.push_back(other);
+            } else {
+                step.from_goal[other] = true;
+                step.back_queue.push_back(other);
+            }
+        }
+        step.finish(next);
+        true
+    }
+}
+
+/// What a search does, in the order it does it. Edges are `(from, to)` and
+/// are classified against the tree of discovery edges built so far.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    /// The search starts a new tree at this vertex.
+    NewRoot(usize),
+    Discover(usize),
+    ExamineEdge(usize, usize),
+    /// Leads to an undiscovered vertex, which is discovered through it.
+    TreeEdge(usize, usize),
+    /// Leads to an ancestor, or is a loop.
+    BackEdge(usize, usize),
+    /// Leads to a descendant that was already discovered.
+    ForwardEdge(usize, usize),
+    /// Leads to any other discovered vertex.
+    CrossEdge(usize, usize),
+    /// Every edge out of this vertex has been examined.
+    Finish(usize),
+    /// Iterative deepening starts over from its root with this depth limit.
+    Deepen(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeClass {
+    Tree,
+    Back,
+    Forward,
+    Cross,
+}
+
+impl EdgeClass {
+    pub fn name(&self) -> &'static str {
+        match self {
+            EdgeClass::Tree => "tree",
+            EdgeClass::Back => "back",
+            EdgeClass::Forward => "forward",
+            EdgeClass::Cross => "cross",
+        }
+    }
+}
+
+impl Event {
+    /// The edge a classification event is about and its class.
+    pub fn edge_class(&self) -> Option<((usize, usize), EdgeClass)> {
+        match *self {
+            Event::TreeEdge(from, to) => Some(((from, to), EdgeClass::Tree)),
+            Event::BackEdge(from, to) => Some(((from, to), EdgeClass::Back)),
+            Event::ForwardEdge(from, to) => Some(((from, to), EdgeClass::Forward)),
+            Event::CrossEdge(from, to) => Some(((from, to), EdgeClass::Cross)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct SearchStep<S: Search> {
     pub active: usize,
+    /// Edges vertices were discovered through, in discovery order; `(root,
+    /// root)` for roots.
     pub visited: Vec<(usize, usize)>,
+    /// Vertex each vertex was discovered through, and its index in
+    /// `visited`, so neither needs a search through the list.
+    pub parent: Vec<Option<usize>>,
+    pub order: Vec<Option<usize>>,
+    /// Tree edges between each vertex and its root.
+    layer: Vec<Option<usize>>,
+    /// Preorder numbers of each BFS tree vertex and of the last of its
+    /// descendants, so ancestry takes two comparisons.
+    span: Vec<Option<(usize, usize)>>,
+    /// BFS frontier.
     pub queue: VecDeque<usize>,
+    /// DFS path from the root: each vertex with the neighbours it has yet
+    /// to look at, next one last, filled in once it is on top.
+    pub stack: Vec<(usize, Option<Vec<usize>>)>,
     pub tree: Vec<(usize, usize)>,
+    /// Everything the search has done so far.
+    pub events: Vec<Event>,
+    /// Time each vertex was discovered and finished at, one clock for both.
+    pub discovered: Vec<Option<usize>>,
+    pub finished: Vec<Option<usize>>,
+    time: usize,
+    /// Vertex the search started from and the one it looks for, if any.
+    pub root: usize,
+    pub goal: Option<usize>,
+    pub found: bool,
+    /// Cheapest known way to each vertex: summed edge lengths for the
+    /// best-first searches, depth for iterative deepening.
+    pub cost: Vec<f32>,
+    /// Depth iterative deepening stops at.
+    limit: usize,
+    /// Frontier of the half of a bidirectional search that starts at the
+    /// goal, and the vertices that half has reached.
+    back_queue: VecDeque<usize>,
+    from_goal: Vec<bool>,
+    pub neighbour_order: Order,
+    /// Roots yet to try once a search tree is done, next one last.
+    roots: Option<Vec<usize>>,
     marker: PhantomData<S>,
 }
 
 impl<S: Search> SearchStep<S> {
     pub fn new(active: usize, size: usize) -> Self {
-        let mut visited = Vec::with_capacity(size);
-        visited.push((active, active));
-        let mut queue = VecDeque::with_capacity(size);
-        queue.push_back(active);
+        Self::search(active, None, size)
+    }
 
-        Self {
+    /// A search from `active` that stops once it reaches `goal`.
+    pub fn towards(active: usize, goal: usize, size: usize) -> Self {
+        Self::search(active, Some(goal), size)
+    }
+
+    fn search(active: usize, goal: Option<usize>, size: usize) -> Self {
+        let mut step = Self {
             active,
-            visited,
-            queue,
+            visited: Vec::with_capacity(size),
+            parent: vec![None; size],
+            order: vec![None; size],
+            layer: vec![None; size],
+            span: vec![None; size],
+            queue: VecDeque::with_capacity(size),
+            stack: Vec::with_capacity(size),
             tree: Vec::with_capacity(size),
+            events: Vec::new(),
+            discovered: vec![None; size],
+            finished: vec![None; size],
+            time: 0,
+            root: active,
+            goal,
+            found: false,
+            cost: vec![f32::INFINITY; size],
+            limit: 0,
+            back_queue: VecDeque::new(),
+            from_goal: vec![false; size],
+            neighbour_order: Order::Index,
+            roots: None,
             marker: PhantomData,
+        };
+        S::start(&mut step, active);
+        step
+    }
+
+    /// Records `vertex` as discovered through `parent`, or as a new root
+    /// when they are the same.
+    fn discover(&mut self, parent: usize, vertex: usize) {
+        self.events.push(if parent == vertex {
+            Event::NewRoot(vertex)
+        } else {
+            Event::TreeEdge(parent, vertex)
+        });
+        self.reach(parent, vertex);
+    }
+
+    /// Records `vertex` as discovered through `parent`, leaving the tree
+    /// edge for the caller to report.
+    fn reach(&mut self, parent: usize, vertex: usize) {
+        self.parent[vertex] = Some(parent);
+        self.layer[vertex] = self.layer_under(parent, vertex);
+        self.order[vertex] = Some(self.visited.len());
+        self.visited.push((parent, vertex));
+        self.events.push(Event::Discover(vertex));
+        self.discovered[vertex] = Some(self.time);
+        self.time += 1;
+    }
+
+    fn finish(&mut self, vertex: usize) {
+        self.events.push(Event::Finish(vertex));
+        self.finished[vertex] = Some(self.time);
+        self.time += 1;
+    }
+
+    /// Same search, taking neighbours in `order`.
+    pub fn ordered(mut self, order: Order) -> Self {
+        self.neighbour_order = order;
+        self
+    }
+
+    /// `vertices`, seen from `from`, in the order of the search.
+    fn sorted(&self, from: usize, vertices: &[usize], costs: &Costs) -> Vec<usize> {
+        let mut vertices = vertices.to_vec();
+        self.neighbour_order.sort(from, &mut vertices, costs);
+        vertices
+    }
+
+    /// Vertices `vertex` has edges to, in the order of the search.
+    fn neighbours(&self, graph: &AdjList, vertex: usize, costs: &Costs) -> Vec<usize> {
+        self.sorted(vertex, graph.successors(vertex), costs)
+    }
+
+    /// Takes the next neighbour to look at off the vertex on top of the
+    /// stack.
+    fn next_neighbour(&mut self, graph: &AdjList, costs: &Costs) -> Option<usize> {
+        let (vertex, remaining) = self.stack.last()?;
+        if remaining.is_none() {
+            let mut neighbours = self.neighbours(graph, *vertex, costs);
+            neighbours.reverse();
+            self.stack.last_mut()?.1 = Some(neighbours);
         }
+        self.stack.last_mut()?.1.as_mut()?.pop()
     }
-}
 
-pub trait Queue {
-    fn push_queue(&mut self, value: usize);
-    fn pop_queue(&mut self) -> Option<usize>;
-}
+    /// Undiscovered vertex to start the next search tree at, taken in the
+    /// order of the search as seen from the first root.
+    fn next_root(&mut self, costs: &Costs) -> Option<usize> {
+        if self.roots.is_none() {
+            let all: Vec<usize> = (0..self.discovered.len()).collect();
+            let mut roots = self.sorted(self.root, &all, costs);
+            roots.reverse();
+            self.roots = Some(roots);
+        }
+        let roots = self.roots.as_mut()?;
+        while let Some(root) = roots.pop() {
+            if self.discovered[root].is_none() {
+                return Some(root);
+            }
+        }
+        None
+    }
 
-impl Queue for SearchStep<Bfs> {
-    fn push_queue(&mut self, value: usize) {
-        self.queue.push_back(value);
+    /// Layer of `vertex` as a child of `parent`, 0 for roots.
+    fn layer_under(&self, parent: usize, vertex: usize) -> Option<usize> {
+        if parent == vertex {
+            Some(0)
+        } else {
+            self.layer[parent].map(|layer| layer + 1)
+        }
     }
 
-    fn pop_queue(&mut self) -> Option<usize> {
-        self.queue.pop_front()
+    /// Makes `parent` the vertex `vertex` was discovered through.
+    fn reparent(&mut self, parent: usize, vertex: usize) {
+        self.parent[vertex] = Some(parent);
+        self.layer[vertex] = self.layer_under(parent, vertex);
+        if let Some(index) = self.order[vertex] {
+            self.visited[index].0 = parent;
+        }
     }
-}
 
-impl Queue for SearchStep<Dfs> {
-    fn push_queue(&mut self, value: usize) {
-        self.queue.push_back(value);
+    /// Forgets everything but the events and the clock, so the search can
+    /// start over.
+    fn restart(&mut self) {
+        self.visited.clear();
+        self.parent.fill(None);
+        self.order.fill(None);
+        self.layer.fill(None);
+        self.span.fill(None);
+        self.stack.clear();
+        self.tree.clear();
+        self.discovered.fill(None);
+        self.finished.fill(None);
+        self.cost.fill(f32::INFINITY);
     }
 
-    fn pop_queue(&mut self) -> Option<usize> {
-        self.queue.pop_back()
+    /// Class the edge `(from, to)` was last given, since the search last
+    /// started over.
+    pub fn edge_class(&self, from: usize, to: usize) -> Option<EdgeClass> {
+        self.events
+            .iter()
+            .rev()
+            .take_while(|event| !matches!(event, Event::Deepen(_)))
+            .filter_map(Event::edge_class)
+            .find(|(edge, _)| *edge == (from, to))
+            .map(|(_, class)| class)
+    }
+
+    /// Numbers the tree BFS grows from `root` in preorder, before it is
+    /// grown: the search runs ahead the way it will run step by step, so
+    /// the tree comes out the same. Numbers carry on from earlier trees.
+    fn index_tree(&mut self, graph: &AdjList, root: usize, costs: &Costs) {
+        // tree vertices in the order BFS discovers them, and the range of
+        // that list its children take, which BFS discovers one after another
+        let mut reached = vec![root];
+        let mut children = Vec::new();
+        self.span[root] = Some((0, 0));
+        let mut next = 0;
+        while next < reached.len() {
+            let first = reached.len();
+            for to in self.neighbours(graph, reached[next], costs) {
+                if self.span[to].is_none() && self.discovered[to].is_none() {
+                    self.span[to] = Some((0, 0));
+                    reached.push(to);
+                }
+            }
+            children.push(first..reached.len());
+            next += 1;
+        }
+        // children come after their parents, sizes are summed from the end
+        let mut size = vec![1; reached.len()];
+        for index in (0..reached.len()).rev() {
+            size[index] += children[index]
+                .clone()
+                .map(|child| size[child])
+                .sum::<usize>();
+        }
+        let mut number = vec![self.visited.len() - 1; reached.len()];
+        for index in 0..reached.len() {
+            let mut first = number[index] + 1;
+            for child in children[index].clone() {
+                number[child] = first;
+                first += size[child];
+            }
+            self.span[reached[index]] = Some((number[index], number[index] + size[index] - 1));
+        }
+    }
+
+    /// Whether `ancestor` lies on the path from `vertex` to its root,
+    /// `vertex` included, in a BFS tree `index_tree` has numbered.
+    fn is_ancestor(&self, ancestor: usize, vertex: usize) -> bool {
+        match (self.span[ancestor], self.span[vertex]) {
+            (Some((first, last)), Some((number, _))) => first <= number && number <= last,
+            _ => false,
+        }
+    }
+
+    /// Position of `vertex` in the new vertex order, counting from 1.
+    pub fn discovery_order(&self, vertex: usize) -> Option<usize> {
+        self.order[vertex].map(|index| index + 1)
+    }
+
+    /// Edges between `vertex` and the root of its search tree, following the
+    /// edges it was discovered through. For BFS this is the distance from
+    /// the root, its layer.
+    pub fn depth(&self, vertex: usize) -> Option<usize> {
+        self.layer[vertex]
+    }
+
+    /// Vertices from the root of its search tree to `vertex`, following the
+    /// parents. For BFS this is a shortest path by edge count.
+    pub fn path_to(&self, vertex: usize) -> Option<Vec<usize>> {
+        let mut path = vec![vertex];
+        let mut current = vertex;
+        loop {
+            let parent = self.parent[current]?;
+            if parent == current {
+                path.reverse();
+                return Some(path);
+            }
+            path.push(parent);
+            current = parent;
+        }
+    }
+
+    /// Renumbers the vertices in the order the search discovered them, the
+    /// ones it never reached after those in their old order. Returns the
+    /// permutation `AdjMatrix::permuted` takes.
+    pub fn renumbering(&self) -> Vec<usize> {
+        let mut vertices: Vec<usize> = (0..self.order.len()).collect();
+        vertices.sort_by_key(|vertex| self.order[*vertex].unwrap_or(usize::MAX));
+        permutation(&vertices)
     }
 }
 
+/// Permutation that gives `vertices[i]` the new number `i`, from a list of
+/// every vertex in its new order.
+pub fn permutation(vertices: &[usize]) -> Vec<usize> {
+    let mut result = vec![usize::MAX; vertices.len()];
+    for (new, old) in vertices.iter().enumerate() {
+        result[*old] = new;
+    }
+    assert!(
+        result.iter().all(|new| *new != usize::MAX),
+        "not a vertex order: {:?}",
+        vertices
+    );
+    result
+}
+
 impl AdjMatrix {
-    pub fn generate(k: f32) -> Self {
-        let mut rng = SmallRng::seed_from_u64(RANDOM_SEED);
+    pub fn generate(size: usize, seed: u64, k: f32) -> Self {
+        let mut rng = Xoshiro256::seed_from_u64(seed);
         let iter = std::iter::repeat_with(move || rng.random_range(0.0..2.0));
 
         AdjMatrix(
-            iter.take(VERTEX_COUNT * VERTEX_COUNT)
+            iter.take(size * size)
                 .map(|i| f32::min(i * k, 1.0) as u32)
                 .collect::<Vec<_>>()
-                .chunks(VERTEX_COUNT)
+                .chunks(size)
                 .map(|row| row.to_vec())
                 .collect(),
         )
     }
 
-    pub fn search_next<S: Search>(&self, step: &mut SearchStep<S>) -> bool
-    where
-        SearchStep<S>: Queue,
-    {
-        if let Some(next) = step.pop_queue() {
-            step.active = next;
-            step.tree.push(
-                *step
-                    .visited
+    // one row per line, entries separated by spaces: what `Display` prints,
+    // without the colours
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        let contents: String = self
+            .0
+            .iter()
+            .map(|row| {
+                let row: Vec<String> = row.iter().map(|value| value.to_string()).collect();
+                row.join(" ") + "\n"
+            })
+            .collect();
+        fs::write(path, contents)
+    }
+
+    /// Reads a matrix `save` wrote, or one copied from the program's
+    /// output, colours and all.
+    pub fn load(path: &str) -> io::Result<Self> {
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+        let rows = fs::read_to_string(path)?
+            .replace("\x1b[31m", "")
+            .replace("\x1b[0m", "")
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                line.split_whitespace()
+                    .map(|value| match value {
+                        "0" => Ok(0),
+                        "1" => Ok(1),
+                        _ => Err(invalid(format!("expected 0 or 1, got `{}`", value))),
+                    })
+                    .collect::<io::Result<Vec<u32>>>()
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+        match rows.iter().find(|row| row.len() != rows.len()) {
+            Some(row) => Err(invalid(format!(
+                "{} rows, but a row of {} entries",
+                rows.len(),
+                row.len()
+            ))),
+            None => Ok(AdjMatrix(rows)),
+        }
+    }
+
+    /// Every `(from, to)` pair with a 1 in the matrix.
+    pub fn edges(&self) -> Vec<(usize, usize)> {
+        (0..self.0.len())
+            .flat_map(|i| (0..self.0.len()).map(move |j| (i, j)))
+            .filter(|(i, j)| self.0[*i][*j] == 1)
+            .collect()
+    }
+
+    /// The same graph with vertex `i` renumbered to `permutation[i]`.
+    pub fn permuted(&self, permutation: &[usize]) -> AdjMatrix {
+        let size = self.0.len();
+        let mut taken = vec![false; size];
+        assert!(
+            permutation.len() == size
+                && permutation
                     .iter()
-                    .find(|(_, to)| *to == next)
-                    .unwrap_or(&(next, next)),
-            );
-            self.0[next].iter().enumerate().for_each(|(i, edge)| {
-                if *edge == 1 && !step.visited.iter().any(|(_, to)| *to == i) {
-                    step.visited.push((next, i));
-                    step.push_queue(i);
+                    .all(|new| *new < size && !std::mem::replace(&mut taken[*new], true)),
+            "not a permutation of {} vertices: {:?}",
+            size,
+            permutation
+        );
+        // $\mathtt{result[p_i][p_j] = matrix[i][j]}$
+        let mut result = vec![vec![0; size]; size];
+        for i in 0..size {
+            for j in 0..size {
+                result[permutation[i]][permutation[j]] = self.0[i][j];
+            }
+        }
+        AdjMatrix(result)
+    }
+
+    /// Vertices joined to `vertex` by an edge either way, loops left out.
+    fn undirected_neighbours(&self, vertex: usize) -> Vec<usize> {
+        (0..self.0.len())
+            .filter(|other| {
+                *other != vertex && (self.0[vertex][*other] == 1 || self.0[*other][vertex] == 1)
+            })
+            .collect()
+    }
+
+    /// Renumbers the vertices from the highest in + out degree down, ties
+    /// keeping their old order.
+    pub fn degree_order(&self) -> Vec<usize> {
+        let degree = |vertex: usize| {
+            self.0[vertex].iter().sum::<u32>() + self.0.iter().map(|row| row[vertex]).sum::<u32>()
+        };
+        let mut vertices: Vec<usize> = (0..self.0.len()).collect();
+        vertices.sort_by_key(|vertex| std::cmp::Reverse(degree(*vertex)));
+        permutation(&vertices)
+    }
+
+    /// Reverse Cuthill–McKee: a BFS of every component from its vertex of
+    /// lowest degree, neighbours taken by increasing degree, numbered
+    /// backwards. Neighbours end up with close numbers, so the ones of the
+    /// permuted matrix gather around the diagonal and `bandwidth` drops.
+    /// Edge directions are ignored.
+    pub fn reverse_cuthill_mckee(&self) -> Vec<usize> {
+        let size = self.0.len();
+        let neighbours: Vec<Vec<usize>> = (0..size)
+            .map(|vertex| self.undirected_neighbours(vertex))
+            .collect();
+        let mut visited = vec![false; size];
+        let mut vertices = Vec::with_capacity(size);
+        while let Some(root) = (0..size)
+            .filter(|vertex| !visited[*vertex])
+            .min_by_key(|vertex| neighbours[*vertex].len())
+        {
+            visited[root] = true;
+            let mut queue = VecDeque::from([root]);
+            while let Some(vertex) = queue.pop_front() {
+                vertices.push(vertex);
+                let mut next: Vec<usize> = neighbours[vertex]
+                    .iter()
+                    .copied()
+                    .filter(|other| !visited[*other])
+                    .collect();
+                next.sort_by_key(|other| neighbours[*other].len());
+                for other in next {
+                    visited[other] = true;
+                    queue.push_back(other);
                 }
-            });
-            true
-        } else {
-            if let Some(unvisited) =
-                (0..self.0.len()).find(|vertex| !step.tree.iter().any(|(_, to)| to == vertex))
-            {
-                step.push_queue(unvisited);
-                self.search_next::<S>(step);
-                return true;
             }
-            false
         }
+        vertices.reverse();
+        permutation(&vertices)
+    }
+
+    /// Largest distance between the row and column of a one, how far the
+    /// edges stray from the diagonal.
+    pub fn bandwidth(&self) -> usize {
+        self.edges()
+            .iter()
+            .map(|(i, j)| i.abs_diff(*j))
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// Successors and predecessors of every vertex, which is what the searches
+/// walk: a step costs the degree of the vertex instead of a whole matrix
+/// row, and sparse graphs too big for a matrix fit.
+#[derive(Clone)]
+pub struct AdjList {
+    successors: Vec<Vec<usize>>,
+    predecessors: Vec<Vec<usize>>,
+}
+
+impl AdjList {
+    pub fn new(vertex_count: usize, edges: &[(usize, usize)]) -> Self {
+        let mut successors = vec![Vec::new(); vertex_count];
+        let mut predecessors = vec![Vec::new(); vertex_count];
+        for (from, to) in edges {
+            successors[*from].push(*to);
+            predecessors[*to].push(*from);
+        }
+        // neighbours in index order, which is what `Order::Index` means
+        successors.iter_mut().for_each(|list| list.sort_unstable());
+        predecessors
+            .iter_mut()
+            .for_each(|list| list.sort_unstable());
+        AdjList {
+            successors,
+            predecessors,
+        }
+    }
+
+    pub fn successors(&self, vertex: usize) -> &[usize] {
+        &self.successors[vertex]
+    }
+
+    pub fn predecessors(&self, vertex: usize) -> &[usize] {
+        &self.predecessors[vertex]
+    }
+
+    pub fn search_next<S: Search>(&self, step: &mut SearchStep<S>, costs: &Costs) -> bool {
+        S::next(self, step, costs)
+    }
+
+    /// Runs the search from `step` to the end, yielding its events one by
+    /// one, those already in `step` first.
+    pub fn traverse<'a, S: Search>(
+        &'a self,
+        step: SearchStep<S>,
+        costs: Costs<'a>,
+    ) -> Traversal<'a, S> {
+        Traversal {
+            graph: self,
+            step,
+            costs,
+            emitted: 0,
+        }
+    }
+}
+
+impl From<&AdjMatrix> for AdjList {
+    fn from(value: &AdjMatrix) -> Self {
+        AdjList::new(value.0.len(), &value.edges())
+    }
+}
+
+pub struct Traversal<'a, S: Search> {
+    graph: &'a AdjList,
+    step: SearchStep<S>,
+    costs: Costs<'a>,
+    emitted: usize,
+}
+
+impl<S: Search> Iterator for Traversal<'_, S> {
+    type Item = Event;
+
+    fn next(&mut self) -> Option<Event> {
+        while self.emitted == self.step.events.len() {
+            if !self.graph.search_next(&mut self.step, &self.costs) {
+                return None;
+            }
+        }
+        self.emitted += 1;
+        Some(self.step.events[self.emitted - 1])
     }
 }
 
 impl<S: Search> From<&SearchStep<S>> for AdjMatrix {
     fn from(value: &SearchStep<S>) -> Self {
-        let size = value.tree.len();
+        let size = value.discovered.len();
         let mut result = vec![vec![0_u32; size]; size];
         for (from, to) in &value.tree {
             if *from != *to {
@@ -150,3 +1065,789 @@ impl<S: Search> From<&SearchStep<S>> for AdjMatrix {
         AdjMatrix(result)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variant::{Lab, Variant};
+
+    fn matrix(rows: &[[u32; 12]]) -> Vec<Vec<u32>> {
+        rows.iter().map(|row| row.to_vec()).collect()
+    }
+
+    fn unit(_: usize, _: usize) -> f32 {
+        1.0
+    }
+
+    /// Every edge as long as every other, no estimate to go on.
+    fn unit_costs() -> Costs<'static> {
+        Costs {
+            length: &unit,
+            heuristic: &unit,
+        }
+    }
+
+    /// Small directed graphs, loops and all.
+    fn small_graphs() -> impl Iterator<Item = AdjMatrix> {
+        (0..60).map(|seed| AdjMatrix::generate(1 + seed as usize % 9, seed, 0.65))
+    }
+
+    /// Fewest edges from `from` to every vertex, relaxing every edge until
+    /// nothing changes.
+    fn hops(graph: &AdjMatrix, from: usize) -> Vec<Option<usize>> {
+        let mut hops = vec![None; graph.0.len()];
+        hops[from] = Some(0);
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (i, j) in graph.edges() {
+                if let Some(through) = hops[i].map(|hops: usize| hops + 1)
+                    && hops[j].is_none_or(|hops| through < hops)
+                {
+                    hops[j] = Some(through);
+                    changed = true;
+                }
+            }
+        }
+        hops
+    }
+
+    fn finished<S: Search>(
+        graph: &AdjList,
+        mut step: SearchStep<S>,
+        costs: &Costs,
+    ) -> SearchStep<S> {
+        while graph.search_next(&mut step, costs) {}
+        step
+    }
+
+    /// Random points for the vertices of a graph of `size`.
+    fn points(size: usize, seed: u64) -> Vec<(f32, f32)> {
+        let mut rng = Xoshiro256::seed_from_u64(seed);
+        (0..size)
+            .map(|_| (rng.random_range(0.0..100.0), rng.random_range(0.0..100.0)))
+            .collect()
+    }
+
+    /// Straight-line distance between two of `points`.
+    fn distance(points: &[(f32, f32)]) -> impl Fn(usize, usize) -> f32 + '_ {
+        |from, to| {
+            let ((x1, y1), (x2, y2)) = (points[from], points[to]);
+            (x1 - x2).hypot(y1 - y2)
+        }
+    }
+
+    /// Shortest summed `length` from `from` to every vertex, relaxing every
+    /// edge until nothing changes.
+    fn shortest(graph: &AdjMatrix, from: usize, length: impl Fn(usize, usize) -> f32) -> Vec<f32> {
+        let mut shortest = vec![f32::INFINITY; graph.0.len()];
+        shortest[from] = 0.0;
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (i, j) in graph.edges() {
+                if shortest[i] + length(i, j) < shortest[j] {
+                    shortest[j] = shortest[i] + length(i, j);
+                    changed = true;
+                }
+            }
+        }
+        shortest
+    }
+
+    /// Checks `step` run to the end through `traverse` against stepping it
+    /// by hand, and that every vertex is discovered, has its edges examined
+    /// and is finished once, in that order.
+    fn check_traversal<S: Search + Clone + Debug + PartialEq>(
+        matrix: &AdjMatrix,
+        step: SearchStep<S>,
+    ) {
+        let graph = AdjList::from(matrix);
+        let events: Vec<Event> = graph.traverse(step.clone(), unit_costs()).collect();
+        assert_eq!(events, finished(&graph, step, &unit_costs()).events);
+
+        let position = |wanted: Event| events.iter().position(|event| *event == wanted);
+        let mut examined = Vec::new();
+        for vertex in 0..matrix.0.len() {
+            let discover = position(Event::Discover(vertex)).unwrap();
+            let finish = position(Event::Finish(vertex)).unwrap();
+            assert!(discover < finish, "{} {}", S::NAME, vertex);
+            assert!(
+                matches!(
+                    events[discover - 1],
+                    Event::NewRoot(root) if root == vertex
+                ) || matches!(
+                    events[discover - 1],
+                    Event::TreeEdge(_, to) if to == vertex
+                )
+            );
+            for (index, event) in events.iter().enumerate() {
+                if let Event::ExamineEdge(from, to) = *event
+                    && from == vertex
+                {
+                    assert!(
+                        discover < index && index < finish,
+                        "{} {:?}",
+                        S::NAME,
+                        event
+                    );
+                    examined.push((from, to));
+                }
+            }
+        }
+        let mut discovered: Vec<usize> = events
+            .iter()
+            .filter_map(|event| match event {
+                Event::Discover(vertex) => Some(*vertex),
+                _ => None,
+            })
+            .collect();
+        discovered.sort();
+        assert_eq!(discovered, (0..matrix.0.len()).collect::<Vec<_>>());
+        let finishes = events
+            .iter()
+            .filter(|event| matches!(event, Event::Finish(_)))
+            .count();
+        assert_eq!(finishes, matrix.0.len());
+        examined.sort();
+        assert_eq!(examined, matrix.edges(), "{}", S::NAME);
+    }
+
+    fn from_edges(size: usize, edges: &[(usize, usize)]) -> AdjMatrix {
+        let mut matrix = AdjMatrix(vec![vec![0; size]; size]);
+        for (from, to) in edges {
+            matrix.0[*from][*to] = 1;
+        }
+        matrix
+    }
+
+    #[test]
+    fn bfs_events() {
+        use Event::*;
+        let matrix = from_edges(4, &[(0, 1), (0, 2), (1, 2), (2, 0)]);
+        let graph = AdjList::from(&matrix);
+        let events: Vec<Event> = graph
+            .traverse(SearchStep::<Bfs>::new(0, 4), unit_costs())
+            .collect();
+        assert_eq!(
+            events,
+            [
+                NewRoot(0),
+                Discover(0),
+                ExamineEdge(0, 1),
+                TreeEdge(0, 1),
+                Discover(1),
+                ExamineEdge(0, 2),
+                TreeEdge(0, 2),
+                Discover(2),
+                Finish(0),
+                ExamineEdge(1, 2),
+                CrossEdge(1, 2),
+                Finish(1),
+                ExamineEdge(2, 0),
+                BackEdge(2, 0),
+                Finish(2),
+                NewRoot(3),
+                Discover(3),
+                Finish(3),
+            ]
+        );
+    }
+
+    // 4 and 5 cannot be reached from 0, DFS starts a second tree at 4
+    #[test]
+    fn dfs_classifies_edges_and_times_vertices() {
+        let matrix = from_edges(
+            6,
+            &[
+                (0, 1),
+                (0, 2),
+                (0, 3),
+                (1, 2),
+                (2, 0),
+                (3, 1),
+                (4, 5),
+                (5, 1),
+                (5, 5),
+            ],
+        );
+        let step = finished(
+            &AdjList::from(&matrix),
+            SearchStep::<Dfs>::new(0, 6),
+            &unit_costs(),
+        );
+        let classes = [
+            ((0, 1), EdgeClass::Tree),
+            ((0, 2), EdgeClass::Forward),
+            ((0, 3), EdgeClass::Tree),
+            ((1, 2), EdgeClass::Tree),
+            ((2, 0), EdgeClass::Back),
+            ((3, 1), EdgeClass::Cross),
+            ((4, 5), EdgeClass::Tree),
+            ((5, 1), EdgeClass::Cross),
+            ((5, 5), EdgeClass::Back),
+        ];
+        for ((from, to), class) in classes {
+            assert_eq!(step.edge_class(from, to), Some(class), "{} -> {}", from, to);
+        }
+        let times =
+            |times: &[Option<usize>]| times.iter().map(|time| time.unwrap()).collect::<Vec<_>>();
+        assert_eq!(times(&step.discovered), [0, 1, 2, 5, 8, 9]);
+        assert_eq!(times(&step.finished), [7, 4, 3, 6, 11, 10]);
+        assert_eq!(
+            step.parent,
+            [Some(0), Some(0), Some(1), Some(0), Some(4), Some(4)]
+        );
+        assert!(step.events.contains(&Event::NewRoot(4)));
+    }
+
+    // each class is a way the intervals from discovery to finish can nest
+    #[test]
+    fn dfs_classes_match_the_times() {
+        for matrix in small_graphs() {
+            let size = matrix.0.len();
+            for start in 0..size {
+                let step = finished(
+                    &AdjList::from(&matrix),
+                    SearchStep::<Dfs>::new(start, size),
+                    &unit_costs(),
+                );
+                let span = |vertex: usize| {
+                    (
+                        step.discovered[vertex].unwrap(),
+                        step.finished[vertex].unwrap(),
+                    )
+                };
+                for (from, to) in matrix.edges() {
+                    let ((d_from, f_from), (d_to, f_to)) = (span(from), span(to));
+                    match step.edge_class(from, to).unwrap() {
+                        EdgeClass::Tree => {
+                            assert_eq!(step.parent[to], Some(from));
+                            assert!(d_from < d_to && f_to < f_from);
+                        }
+                        EdgeClass::Forward => assert!(d_from < d_to && f_to < f_from),
+                        EdgeClass::Back => assert!(d_to <= d_from && f_from <= f_to),
+                        EdgeClass::Cross => assert!(f_to < d_from),
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn weight_order_takes_shortest_edges_first() {
+        let lengths = [0.0, 5.0, 1.0, 4.0, 2.0, 3.0];
+        let length = |_: usize, to: usize| lengths[to];
+        let costs = Costs {
+            length: &length,
+            heuristic: &length,
+        };
+        let mut vertices = vec![1, 2, 3, 4, 5];
+        Order::Weight.sort(0, &mut vertices, &costs);
+        assert_eq!(vertices, [2, 4, 5, 3, 1]);
+
+        let star = from_edges(6, &[(0, 1), (0, 2), (0, 3), (0, 4), (0, 5)]);
+        let step = finished(
+            &AdjList::from(&star),
+            SearchStep::<Bfs>::new(0, 6).ordered(Order::Weight),
+            &costs,
+        );
+        let discovered: Vec<usize> = step.visited.iter().map(|(_, to)| *to).collect();
+        assert_eq!(discovered, [0, 2, 4, 5, 3, 1]);
+    }
+
+    #[test]
+    fn random_order_follows_the_seed() {
+        let shuffled = |seed: u64, from: usize| {
+            let mut vertices: Vec<usize> = (0..10).collect();
+            Order::Random(seed).sort(from, &mut vertices, &unit_costs());
+            vertices
+        };
+        assert_eq!(shuffled(1, 0), shuffled(1, 0));
+        assert_ne!(shuffled(1, 0), shuffled(2, 0));
+        let mut sorted = shuffled(1, 0);
+        sorted.sort();
+        assert_eq!(sorted, (0..10).collect::<Vec<_>>());
+
+        let matrix = AdjMatrix::generate(11, 4228, 0.8);
+        let graph = AdjList::from(&matrix);
+        let events = |seed: u64| {
+            finished(
+                &graph,
+                SearchStep::<Dfs>::new(0, 11).ordered(Order::Random(seed)),
+                &unit_costs(),
+            )
+            .events
+        };
+        assert_eq!(events(1), events(1));
+        assert_ne!(events(1), events(2));
+    }
+
+    /// Discovery order and parents of a plain BFS or DFS taking neighbours
+    /// and roots in `order`, to check the stepped ones against.
+    fn reference(
+        matrix: &AdjMatrix,
+        start: usize,
+        order: Order,
+        depth_first: bool,
+        costs: &Costs,
+    ) -> (Vec<usize>, Vec<Option<usize>>) {
+        fn visit(
+            graph: &AdjList,
+            vertex: usize,
+            order: Order,
+            costs: &Costs,
+            visited: &mut Vec<usize>,
+            parent: &mut [Option<usize>],
+        ) {
+            let mut next = graph.successors(vertex).to_vec();
+            order.sort(vertex, &mut next, costs);
+            for to in next {
+                if parent[to].is_none() {
+                    parent[to] = Some(vertex);
+                    visited.push(to);
+                    visit(graph, to, order, costs, visited, parent);
+                }
+            }
+        }
+        let graph = AdjList::from(matrix);
+        let size = matrix.0.len();
+        let mut roots: Vec<usize> = (0..size).collect();
+        order.sort(start, &mut roots, costs);
+        let mut visited = Vec::new();
+        let mut parent = vec![None; size];
+        for root in std::iter::once(start).chain(roots) {
+            if parent[root].is_some() {
+                continue;
+            }
+            parent[root] = Some(root);
+            visited.push(root);
+            if depth_first {
+                visit(&graph, root, order, costs, &mut visited, &mut parent);
+                continue;
+            }
+            let mut queue = VecDeque::from([root]);
+            while let Some(vertex) = queue.pop_front() {
+                let mut next = graph.successors(vertex).to_vec();
+                order.sort(vertex, &mut next, costs);
+                for to in next {
+                    if parent[to].is_none() {
+                        parent[to] = Some(vertex);
+                        visited.push(to);
+                        queue.push_back(to);
+                    }
+                }
+            }
+        }
+        (visited, parent)
+    }
+
+    #[test]
+    fn discovery_order_and_parents() {
+        fn check<S: Search>(
+            matrix: &AdjMatrix,
+            step: SearchStep<S>,
+            depth_first: bool,
+            costs: &Costs,
+        ) {
+            let (start, order) = (step.root, step.neighbour_order);
+            let step = finished(&AdjList::from(matrix), step, costs);
+            let (visited, parent) = reference(matrix, start, order, depth_first, costs);
+            assert_eq!(step.parent, parent, "{} {}", S::NAME, order);
+            for (index, vertex) in visited.iter().enumerate() {
+                assert_eq!(step.discovery_order(*vertex), Some(index + 1));
+            }
+        }
+        for (seed, matrix) in small_graphs().enumerate() {
+            let size = matrix.0.len();
+            let points = points(size, seed as u64);
+            let distance = distance(&points);
+            let costs = Costs {
+                length: &distance,
+                heuristic: &distance,
+            };
+            for start in 0..size {
+                for order in [
+                    Order::Index,
+                    Order::Reverse,
+                    Order::Weight,
+                    Order::Random(start as u64),
+                ] {
+                    check(
+                        &matrix,
+                        SearchStep::<Bfs>::new(start, size).ordered(order),
+                        false,
+                        &costs,
+                    );
+                    check(
+                        &matrix,
+                        SearchStep::<Dfs>::new(start, size).ordered(order),
+                        true,
+                        &costs,
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn bfs_ancestry_matches_the_parents() {
+        for (seed, matrix) in small_graphs()
+            .chain((0..20).map(|seed| AdjMatrix::generate(30, seed, 0.55)))
+            .enumerate()
+        {
+            let graph = AdjList::from(&matrix);
+            let size = matrix.0.len();
+            let start = seed % size;
+            let mut step = SearchStep::<Bfs>::new(start, size).ordered(Order::Random(seed as u64));
+            // ancestry is asked for while the tree grows, so check every
+            // step; a tree is numbered once BFS takes its root off the queue
+            while graph.search_next(&mut step, &unit_costs()) {
+                for vertex in (0..size).filter(|vertex| step.parent[*vertex].is_some()) {
+                    let mut ancestors = vec![vertex];
+                    while let Some(parent) = step.parent[*ancestors.last().unwrap()]
+                        .filter(|parent| !ancestors.contains(parent))
+                    {
+                        ancestors.push(parent);
+                    }
+                    for ancestor in 0..size {
+                        assert_eq!(
+                            step.is_ancestor(ancestor, vertex),
+                            ancestors.contains(&ancestor),
+                            "{} of {}",
+                            ancestor,
+                            vertex
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn bfs_depths_paths_and_tree() {
+        for matrix in small_graphs() {
+            let graph = AdjList::from(&matrix);
+            let size = matrix.0.len();
+            for start in 0..size {
+                let step = finished(&graph, SearchStep::<Bfs>::new(start, size), &unit_costs());
+                let hops = hops(&matrix, start);
+                for vertex in reachable(&matrix, start) {
+                    assert_eq!(step.depth(vertex), hops[vertex]);
+                    let path = step.path_to(vertex).unwrap();
+                    assert_eq!((path[0], path.len() - 1), (start, hops[vertex].unwrap()));
+                    assert!(path.windows(2).all(|leg| matrix.0[leg[0]][leg[1]] == 1));
+                }
+                // every vertex out of reach is a root or under one
+                for vertex in (0..size).filter(|vertex| hops[*vertex].is_none()) {
+                    assert_ne!(step.path_to(vertex).unwrap()[0], start);
+                }
+
+                let tree = AdjMatrix::from(&step);
+                let mut edges: Vec<(usize, usize)> = (0..size)
+                    .filter_map(|vertex| step.parent[vertex].map(|parent| (parent, vertex)))
+                    .filter(|(parent, vertex)| parent != vertex)
+                    .collect();
+                edges.sort();
+                assert_eq!(tree.edges(), edges);
+                assert!(edges.iter().all(|(i, j)| matrix.0[*i][*j] == 1));
+            }
+        }
+    }
+
+    #[test]
+    fn traverse_yields_the_events_in_order() {
+        for matrix in small_graphs() {
+            for start in 0..matrix.0.len() {
+                for order in [Order::Index, Order::Reverse, Order::Random(7)] {
+                    let size = matrix.0.len();
+                    check_traversal(&matrix, SearchStep::<Bfs>::new(start, size).ordered(order));
+                    check_traversal(&matrix, SearchStep::<Dfs>::new(start, size).ordered(order));
+                }
+            }
+        }
+    }
+
+    /// Vertices `start` has a path to, itself included.
+    fn reachable(matrix: &AdjMatrix, start: usize) -> Vec<usize> {
+        let hops = hops(matrix, start);
+        (0..matrix.0.len())
+            .filter(|vertex| hops[*vertex].is_some())
+            .collect()
+    }
+
+    #[test]
+    fn searches_reach_every_vertex_once() {
+        fn check<S: Search>(matrix: &AdjMatrix, step: SearchStep<S>) {
+            let graph = AdjList::from(matrix);
+            let start = step.root;
+            let step = finished(&graph, step, &unit_costs());
+            // iterative deepening starts over each round, the last one counts
+            let round = step
+                .events
+                .iter()
+                .rposition(|event| matches!(event, Event::Deepen(_)))
+                .map_or(0, |index| index + 1);
+            let mut vertices: Vec<usize> = step.events[round..]
+                .iter()
+                .filter_map(|event| match event {
+                    Event::Discover(vertex) => Some(*vertex),
+                    _ => None,
+                })
+                .collect();
+            vertices.sort();
+            assert_eq!(vertices, reachable(matrix, start), "{}", S::NAME);
+        }
+        for matrix in small_graphs() {
+            let size = matrix.0.len();
+            for start in 0..size {
+                check(&matrix, SearchStep::<Iddfs>::new(start, size));
+                check(&matrix, SearchStep::<UniformCost>::new(start, size));
+                check(&matrix, SearchStep::<Greedy>::new(start, size));
+                check(&matrix, SearchStep::<AStar>::new(start, size));
+                check(&matrix, SearchStep::<Bidirectional>::new(start, size));
+            }
+        }
+    }
+
+    #[test]
+    fn uniform_cost_and_a_star_find_shortest_paths() {
+        fn check<S: Search>(
+            matrix: &AdjMatrix,
+            step: SearchStep<S>,
+            costs: &Costs,
+            shortest: &[f32],
+        ) {
+            let goal = step.goal.unwrap();
+            let step = finished(&AdjList::from(matrix), step, costs);
+            assert_eq!(step.found, shortest[goal].is_finite(), "{}", S::NAME);
+            if !step.found {
+                return;
+            }
+            assert!(
+                (step.cost[goal] - shortest[goal]).abs() < 1e-3,
+                "{}",
+                S::NAME
+            );
+            let path = step.path_to(goal).unwrap();
+            assert_eq!(path[0], step.root);
+            let length: f32 = path
+                .windows(2)
+                .map(|leg| {
+                    assert_eq!(matrix.0[leg[0]][leg[1]], 1);
+                    (costs.length)(leg[0], leg[1])
+                })
+                .sum();
+            assert!((length - shortest[goal]).abs() < 1e-3, "{}", S::NAME);
+        }
+        for (seed, matrix) in small_graphs().enumerate() {
+            let size = matrix.0.len();
+            let points = points(size, seed as u64);
+            let distance = distance(&points);
+            let costs = Costs {
+                length: &distance,
+                heuristic: &distance,
+            };
+            for start in 0..size {
+                let shortest = shortest(&matrix, start, &distance);
+                for goal in 0..size {
+                    check(
+                        &matrix,
+                        SearchStep::<UniformCost>::towards(start, goal, size),
+                        &costs,
+                        &shortest,
+                    );
+                    check(
+                        &matrix,
+                        SearchStep::<AStar>::towards(start, goal, size),
+                        &costs,
+                        &shortest,
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn bidirectional_meets_on_a_shortest_path() {
+        for matrix in small_graphs() {
+            let graph = AdjList::from(&matrix);
+            let size = matrix.0.len();
+            for start in 0..size {
+                let hops = hops(&matrix, start);
+                for goal in 0..size {
+                    let step = finished(
+                        &graph,
+                        SearchStep::<Bidirectional>::towards(start, goal, size),
+                        &unit_costs(),
+                    );
+                    assert_eq!(step.found, hops[goal].is_some());
+                    if !step.found || goal == start {
+                        continue;
+                    }
+                    // the edge the two halves met on, from the start's half
+                    // to the goal's, each half a tree of its own
+                    let Some(Event::TreeEdge(from, to)) = step
+                        .events
+                        .iter()
+                        .rev()
+                        .find(|event| matches!(event, Event::TreeEdge(..)))
+                        .copied()
+                    else {
+                        panic!("no edge the halves met on");
+                    };
+                    assert_eq!(matrix.0[from][to], 1);
+                    let there = step.path_to(from).unwrap();
+                    let back = step.path_to(to).unwrap();
+                    assert_eq!((there[0], back[0]), (start, goal));
+                    assert_eq!(there.len() + back.len() - 1, hops[goal].unwrap());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn iddfs_finds_the_goal_at_its_depth() {
+        for matrix in small_graphs() {
+            let graph = AdjList::from(&matrix);
+            let size = matrix.0.len();
+            let distances = hops(&matrix, 0);
+            for goal in 0..size {
+                let step = finished(
+                    &graph,
+                    SearchStep::<Iddfs>::towards(0, goal, size),
+                    &unit_costs(),
+                );
+                assert_eq!(step.found, distances[goal].is_some());
+                if let Some(distance) = distances[goal] {
+                    assert_eq!(step.limit, distance);
+                    assert_eq!(step.depth(goal), Some(distance));
+                    assert_eq!(
+                        step.path_to(goal).map(|path| path.len()),
+                        Some(distance + 1)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn iddfs_stops_at_the_eccentricity() {
+        for matrix in small_graphs() {
+            let size = matrix.0.len();
+            // a goal no edge leads to
+            let mut isolated = matrix.clone();
+            isolated.0.iter_mut().for_each(|row| row.push(0));
+            isolated.0.push(vec![0; size + 1]);
+            let graph = AdjList::from(&isolated);
+            for start in 0..size {
+                let distances = hops(&matrix, start);
+                let eccentricity = distances.iter().flatten().max().copied().unwrap();
+                let step = finished(
+                    &graph,
+                    SearchStep::<Iddfs>::towards(start, size, size + 1),
+                    &unit_costs(),
+                );
+                assert!(!step.found);
+                assert_eq!(step.limit, eccentricity);
+                let deepened = step
+                    .events
+                    .iter()
+                    .filter(|event| matches!(event, Event::Deepen(_)))
+                    .count();
+                assert_eq!(deepened, eccentricity);
+                for vertex in 0..size {
+                    assert_eq!(step.depth(vertex), distances[vertex]);
+                }
+            }
+        }
+    }
+
+    fn is_permutation(permutation: &[usize]) -> bool {
+        let mut sorted = permutation.to_vec();
+        sorted.sort();
+        sorted == (0..permutation.len()).collect::<Vec<_>>()
+    }
+
+    #[test]
+    fn renumbering_keeps_the_graph() {
+        for matrix in small_graphs() {
+            let graph = AdjList::from(&matrix);
+            let size = matrix.0.len();
+            let search = finished(&graph, SearchStep::<Bfs>::new(0, size), &unit_costs());
+            for permutation in [
+                matrix.degree_order(),
+                matrix.reverse_cuthill_mckee(),
+                search.renumbering(),
+            ] {
+                assert!(is_permutation(&permutation), "{:?}", permutation);
+                let renumbered = matrix.permuted(&permutation);
+                let mut edges: Vec<(usize, usize)> = matrix
+                    .edges()
+                    .iter()
+                    .map(|(i, j)| (permutation[*i], permutation[*j]))
+                    .collect();
+                edges.sort();
+                assert_eq!(renumbered.edges(), edges);
+            }
+            let by_degree = matrix.permuted(&matrix.degree_order());
+            let degree = |vertex: usize| {
+                by_degree.0[vertex].iter().sum::<u32>()
+                    + by_degree.0.iter().map(|row| row[vertex]).sum::<u32>()
+            };
+            assert!((1..size).all(|vertex| degree(vertex - 1) >= degree(vertex)));
+            // the search numbers its vertices the order it discovers them in
+            for vertex in 0..size {
+                assert_eq!(
+                    search.renumbering()[vertex] + 1,
+                    search.discovery_order(vertex).unwrap()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn reverse_cuthill_mckee_narrows_the_variant_graph() {
+        let variant = Variant::new(4228).unwrap();
+        let matrix = AdjMatrix::generate(
+            variant.vertex_count(),
+            variant.seed(),
+            variant.k(Lab::Traversal),
+        );
+        let renumbered = matrix.permuted(&matrix.reverse_cuthill_mckee());
+        assert!(renumbered.bandwidth() <= matrix.bandwidth());
+
+        // a path numbered out of order comes back in a line
+        let path = from_edges(7, &[(3, 0), (0, 5), (5, 1), (1, 6), (6, 2), (2, 4)]);
+        assert_eq!(path.bandwidth(), 5);
+        assert_eq!(path.permuted(&path.reverse_cuthill_mckee()).bandwidth(), 1);
+    }
+
+    // the matrix `main` prints for the default variant
+    #[test]
+    fn variant_4228() {
+        let variant = Variant::new(4228).unwrap();
+        let generated = AdjMatrix::generate(
+            variant.vertex_count(),
+            variant.seed(),
+            variant.k(Lab::Traversal),
+        );
+        assert_eq!(
+            generated.0,
+            matrix(&[
+                [0, 1, 1, 1, 0, 0, 1, 0, 0, 0, 1, 0],
+                [0, 1, 0, 1, 0, 0, 0, 1, 0, 1, 1, 1],
+                [0, 0, 1, 0, 0, 1, 1, 1, 0, 1, 1, 0],
+                [0, 0, 1, 1, 0, 1, 0, 1, 1, 1, 0, 1],
+                [0, 0, 1, 0, 0, 1, 1, 0, 0, 0, 1, 0],
+                [0, 0, 0, 0, 0, 1, 0, 1, 0, 1, 0, 0],
+                [0, 0, 1, 1, 0, 0, 0, 0, 0, 0, 1, 0],
+                [0, 0, 1, 0, 0, 0, 0, 0, 0, 1, 0, 0],
+                [0, 1, 0, 0, 0, 1, 0, 0, 1, 0, 1, 1],
+                [1, 0, 1, 0, 1, 0, 1, 1, 0, 0, 0, 0],
+                [0, 0, 1, 0, 0, 1, 0, 0, 0, 0, 1, 1],
+                [1, 0, 1, 1, 0, 1, 1, 0, 1, 1, 1, 1],
+            ])
+        );
+    }
+}
@@ -0,0 +1,354 @@
+use crate::graph::{AdjMatrix, permutation};
+
+const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// What every isomorphism keeps for a vertex: its loop, out degree and in
+/// degree.
+type Degrees = (u32, u32, u32);
+
+fn degrees(matrix: &AdjMatrix) -> Vec<Degrees> {
+    (0..matrix.0.len())
+        .map(|vertex| {
+            (
+                matrix.0[vertex][vertex],
+                matrix.0[vertex].iter().sum(),
+                matrix.0.iter().map(|row| row[vertex]).sum(),
+            )
+        })
+        .collect()
+}
+
+/// Rank of every value among the distinct ones, smallest first.
+fn ranks<T: Ord + Clone>(values: &[T]) -> Vec<usize> {
+    let mut distinct = values.to_vec();
+    distinct.sort();
+    distinct.dedup();
+    values
+        .iter()
+        .map(|value| distinct.binary_search(value).unwrap())
+        .collect()
+}
+
+/// Vertices that swap places without changing the graph: the same loop, the
+/// same edges to every other vertex and the same edges either way between
+/// them.
+fn twins(matrix: &AdjMatrix, a: usize, b: usize) -> bool {
+    let m = &matrix.0;
+    m[a][a] == m[b][b]
+        && m[a][b] == m[b][a]
+        && (0..m.len())
+            .filter(|other| *other != a && *other != b)
+            .all(|other| m[a][other] == m[b][other] && m[other][a] == m[other][b])
+}
+
+/// Vertex colours that start as the degrees and split by the colours of the
+/// successors and predecessors until no class splits any more. Colours
+/// rank what they stand for rather than vertex numbers, so isomorphic
+/// graphs get the same ones.
+fn refined_colours(matrix: &AdjMatrix) -> Vec<usize> {
+    let size = matrix.0.len();
+    let mut colours = ranks(&degrees(matrix));
+    loop {
+        let signatures: Vec<(usize, Vec<usize>, Vec<usize>)> = (0..size)
+            .map(|vertex| {
+                let mut successors: Vec<usize> = (0..size)
+                    .filter(|other| matrix.0[vertex][*other] == 1)
+                    .map(|other| colours[other])
+                    .collect();
+                let mut predecessors: Vec<usize> = (0..size)
+                    .filter(|other| matrix.0[*other][vertex] == 1)
+                    .map(|other| colours[other])
+                    .collect();
+                successors.sort();
+                predecessors.sort();
+                (colours[vertex], successors, predecessors)
+            })
+            .collect();
+        // the old colour leads the signature, so classes only ever split
+        let refined = ranks(&signatures);
+        if refined.iter().max() == colours.iter().max() {
+            return refined;
+        }
+        colours = refined;
+    }
+}
+
+/// VF2-style matcher: extends a partial mapping one vertex at a time,
+/// backtracking once no vertex of `theirs` fits the next one of `ours`.
+struct Matcher<'a> {
+    ours: &'a AdjMatrix,
+    theirs: &'a AdjMatrix,
+    our_degrees: Vec<Degrees>,
+    their_degrees: Vec<Degrees>,
+    /// Vertices of `ours` in the order they get mapped, each one with as
+    /// many edges to those before it as possible, so mismatches show early.
+    order: Vec<usize>,
+    mapping: Vec<Option<usize>>,
+    used: Vec<bool>,
+}
+
+impl Matcher<'_> {
+    fn matching_order(matrix: &AdjMatrix, degrees: &[Degrees]) -> Vec<usize> {
+        let size = matrix.0.len();
+        let mut order = Vec::with_capacity(size);
+        let mut placed = vec![false; size];
+        // edges to vertices already in the order
+        let mut links = vec![0; size];
+        while let Some(next) = (0..size)
+            .filter(|vertex| !placed[*vertex])
+            .max_by_key(|vertex| {
+                let (_, out, r#in) = degrees[*vertex];
+                (links[*vertex], out + r#in, std::cmp::Reverse(*vertex))
+            })
+        {
+            placed[next] = true;
+            order.push(next);
+            for other in 0..size {
+                links[other] += matrix.0[next][other] + matrix.0[other][next];
+            }
+        }
+        order
+    }
+
+    /// Whether `theirs` vertex can stand for the `depth`th vertex of `ours`:
+    /// the same degrees, and every edge to the vertices mapped before it
+    /// has its counterpart.
+    fn fits(&self, depth: usize, theirs: usize) -> bool {
+        let ours = self.order[depth];
+        self.our_degrees[ours] == self.their_degrees[theirs]
+            && self.order[..depth].iter().all(|mapped| {
+                let image = self.mapping[*mapped].unwrap();
+                self.ours.0[ours][*mapped] == self.theirs.0[theirs][image]
+                    && self.ours.0[*mapped][ours] == self.theirs.0[image][theirs]
+            })
+    }
+
+    fn extend(&mut self, depth: usize) -> bool {
+        if depth == self.order.len() {
+            return true;
+        }
+        for theirs in 0..self.used.len() {
+            if self.used[theirs] || !self.fits(depth, theirs) {
+                continue;
+            }
+            self.mapping[self.order[depth]] = Some(theirs);
+            self.used[theirs] = true;
+            if self.extend(depth + 1) {
+                return true;
+            }
+            self.used[theirs] = false;
+        }
+        self.mapping[self.order[depth]] = None;
+        false
+    }
+}
+
+/// Search for the canonical vertex order. Vertices are numbered one at a
+/// time, always from the lowest refined colour left, and among those the
+/// ones whose edges to the vertices numbered so far read largest. Ties
+/// branch, and the order whose whole code reads largest wins.
+struct Labelling<'a> {
+    matrix: &'a AdjMatrix,
+    colours: Vec<usize>,
+    placed: Vec<usize>,
+    best: Option<(Vec<u32>, Vec<usize>)>,
+}
+
+impl Labelling<'_> {
+    /// Loop of `vertex`, then its edges to and from every vertex numbered
+    /// so far.
+    fn code(&self, vertex: usize) -> Vec<u32> {
+        let m = &self.matrix.0;
+        std::iter::once(m[vertex][vertex])
+            .chain(
+                self.placed
+                    .iter()
+                    .flat_map(|placed| [m[vertex][*placed], m[*placed][vertex]]),
+            )
+            .collect()
+    }
+
+    fn search(&mut self, code: Vec<u32>) {
+        if let Some((best, _)) = &self.best
+            && code[..] < best[..code.len()]
+        {
+            return;
+        }
+        let size = self.matrix.0.len();
+        if self.placed.len() == size {
+            if self.best.as_ref().is_none_or(|(best, _)| code > *best) {
+                self.best = Some((code, self.placed.clone()));
+            }
+            return;
+        }
+        let unplaced: Vec<usize> = (0..size)
+            .filter(|vertex| !self.placed.contains(vertex))
+            .collect();
+        let colour = unplaced
+            .iter()
+            .map(|vertex| self.colours[*vertex])
+            .min()
+            .unwrap();
+        let candidates: Vec<(Vec<u32>, usize)> = unplaced
+            .into_iter()
+            .filter(|vertex| self.colours[*vertex] == colour)
+            .map(|vertex| (self.code(vertex), vertex))
+            .collect();
+        let top = candidates
+            .iter()
+            .map(|(code, _)| code)
+            .max()
+            .unwrap()
+            .clone();
+        // twins lead to the same matrix, one of them is enough
+        let mut tried: Vec<usize> = Vec::new();
+        for (_, vertex) in candidates.into_iter().filter(|(code, _)| *code == top) {
+            if tried.iter().any(|other| twins(self.matrix, *other, vertex)) {
+                continue;
+            }
+            tried.push(vertex);
+            self.placed.push(vertex);
+            self.search(code.iter().chain(&top).copied().collect());
+            self.placed.pop();
+        }
+    }
+}
+
+impl AdjMatrix {
+    /// Renumbering that turns `self` into `other` if they are the same graph,
+    /// the permutation `permuted` takes. Graphs whose degree sequences
+    /// differ are told apart before any search.
+    pub fn isomorphism(&self, other: &AdjMatrix) -> Option<Vec<usize>> {
+        let size = self.0.len();
+        if other.0.len() != size {
+            return None;
+        }
+        let sorted = |mut degrees: Vec<Degrees>| {
+            degrees.sort();
+            degrees
+        };
+        let (our_degrees, their_degrees) = (degrees(self), degrees(other));
+        if sorted(our_degrees.clone()) != sorted(their_degrees.clone()) {
+            return None;
+        }
+        let mut matcher = Matcher {
+            ours: self,
+            theirs: other,
+            order: Matcher::matching_order(self, &our_degrees),
+            our_degrees,
+            their_degrees,
+            mapping: vec![None; size],
+            used: vec![false; size],
+        };
+        if matcher.extend(0) {
+            Some(matcher.mapping.into_iter().map(Option::unwrap).collect())
+        } else {
+            None
+        }
+    }
+
+    /// The renumbering every graph isomorphic to this one ends up the same
+    /// under, and the matrix it gives.
+    pub fn canonical_form(&self) -> (AdjMatrix, Vec<usize>) {
+        let mut labelling = Labelling {
+            matrix: self,
+            colours: refined_colours(self),
+            placed: Vec::new(),
+            best: None,
+        };
+        labelling.search(Vec::new());
+        let (_, order) = labelling.best.unwrap();
+        let permutation = permutation(&order);
+        (self.permuted(&permutation), permutation)
+    }
+
+    /// FNV-1a hash of the canonical form: equal for isomorphic graphs, and
+    /// the same from one run or machine to the next.
+    pub fn canonical_hash(&self) -> u64 {
+        let (canonical, _) = self.canonical_form();
+        (canonical.0.len() as u64)
+            .to_le_bytes()
+            .into_iter()
+            .chain(
+                canonical
+                    .0
+                    .iter()
+                    .flatten()
+                    .flat_map(|value| value.to_le_bytes()),
+            )
+            .fold(FNV_OFFSET, |hash, byte| {
+                (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Xoshiro256;
+
+    fn shuffled(size: usize, seed: u64) -> Vec<usize> {
+        let mut rng = Xoshiro256::seed_from_u64(seed);
+        let mut permutation: Vec<usize> = (0..size).collect();
+        for i in (1..size).rev() {
+            permutation.swap(i, (rng.next_u64() % (i as u64 + 1)) as usize);
+        }
+        permutation
+    }
+
+    #[test]
+    fn finds_permutations() {
+        for seed in 0..50 {
+            let matrix = AdjMatrix::generate(4 + seed as usize % 8, seed, 0.7);
+            let other = matrix.permuted(&shuffled(matrix.0.len(), seed));
+            let mapping = matrix.isomorphism(&other).unwrap();
+            assert_eq!(matrix.permuted(&mapping).0, other.0);
+        }
+    }
+
+    #[test]
+    fn rejects_same_degrees() {
+        // a 6-cycle and two 3-cycles: in and out degree 1 everywhere
+        let cycle = |edges: &[(usize, usize)]| {
+            let mut matrix = vec![vec![0; 6]; 6];
+            for (from, to) in edges {
+                matrix[*from][*to] = 1;
+            }
+            AdjMatrix(matrix)
+        };
+        let one = cycle(&[(0, 1), (1, 2), (2, 3), (3, 4), (4, 5), (5, 0)]);
+        let two = cycle(&[(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3)]);
+        assert!(one.isomorphism(&two).is_none());
+        assert_ne!(one.canonical_form().0.0, two.canonical_form().0.0);
+        assert_ne!(one.canonical_hash(), two.canonical_hash());
+    }
+
+    #[test]
+    fn canonical_under_permutation() {
+        for seed in 0..50 {
+            let matrix = AdjMatrix::generate(4 + seed as usize % 8, seed, 0.7);
+            let other = matrix.permuted(&shuffled(matrix.0.len(), seed + 1));
+            assert_eq!(matrix.canonical_form().0.0, other.canonical_form().0.0);
+            assert_eq!(matrix.canonical_hash(), other.canonical_hash());
+        }
+    }
+
+    #[test]
+    fn empty_graph() {
+        let empty = AdjMatrix(Vec::new());
+        assert_eq!(empty.isomorphism(&empty), Some(Vec::new()));
+        assert!(empty.canonical_form().0.0.is_empty());
+        assert_eq!(
+            empty.canonical_hash(),
+            AdjMatrix(Vec::new()).canonical_hash()
+        );
+    }
+
+    #[test]
+    fn hash_keeps_edge_counts() {
+        let single = AdjMatrix(vec![vec![0, 0], vec![0, 0]]);
+        let multiple = AdjMatrix(vec![vec![0, 256], vec![0, 0]]);
+        assert_ne!(single.canonical_hash(), multiple.canonical_hash());
+    }
+}
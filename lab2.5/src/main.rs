@@ -1,129 +1,308 @@
 #![allow(clippy::needless_range_loop)]
 
-use graph::{AdjMatrix, Bfs, DEFAULT_ROWS, Dfs, Search, SearchStep, VERTEX_COUNT};
+use graph::{
+    AStar, AdjList, AdjMatrix, Bfs, Bidirectional, Costs, Dfs, EdgeClass, Event, Greedy, Iddfs,
+    Order, Search, SearchStep, UniformCost,
+};
+use layout::{Arrangement, Hover, VertexPos};
 use raylib::{color::Color, prelude::*};
+use timeline::Timeline;
+use variant::{Lab, Variant};
+use view::View;
 mod draw;
 mod graph;
+mod isomorphism;
+mod layout;
+mod rng;
+mod timeline;
+mod variant;
+mod view;
 
 const WIN_WIDTH: i32 = 800;
 const WIN_HEIGHT: i32 = 600;
 const WIN_MARGIN: f32 = 0.8;
+const TOOLTIP_OFFSET: Vector2 = Vector2 { x: 12.0, y: 12.0 };
+const LEGEND_FONT_SIZE: i32 = 20;
+// screen pixels the mouse may move between press and release of a click
+const CLICK_SLOP: f32 = 3.0;
 
-const K: f32 = 1.0 - 2.0 * 0.01 - 8.0 * 0.005 - 0.15;
+fn edge_color(class: EdgeClass) -> Color {
+    match class {
+        EdgeClass::Tree => Color::RED,
+        EdgeClass::Back => Color::BLUE,
+        EdgeClass::Forward => Color::DARKGREEN,
+        EdgeClass::Cross => Color::ORANGE,
+    }
+}
+
+/// What the window shows besides the graph itself.
+#[derive(Clone, Copy)]
+struct Style {
+    hide_edges: bool,
+    /// Colour discovered vertices by their layer in the search tree.
+    layers: bool,
+    /// Show the graph renumbered this way next to its matrix.
+    renumbering: Option<Renumbering>,
+}
+
+/// New vertex numbers <F9> shows the graph with.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Renumbering {
+    Bfs,
+    Dfs,
+    Degree,
+    ReverseCuthillMcKee,
+}
+
+impl Renumbering {
+    /// The one after `current`, `None` past the last one.
+    fn next(current: Option<Self>) -> Option<Self> {
+        match current {
+            None => Some(Renumbering::Bfs),
+            Some(Renumbering::Bfs) => Some(Renumbering::Dfs),
+            Some(Renumbering::Dfs) => Some(Renumbering::Degree),
+            Some(Renumbering::Degree) => Some(Renumbering::ReverseCuthillMcKee),
+            Some(Renumbering::ReverseCuthillMcKee) => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Renumbering::Bfs => "BFS order",
+            Renumbering::Dfs => "DFS order",
+            Renumbering::Degree => "Degree",
+            Renumbering::ReverseCuthillMcKee => "Reverse Cuthill-McKee",
+        }
+    }
+
+    /// `graph_4228_<suffix>.txt` is where <F5> saves the renumbered graph.
+    fn file_suffix(self) -> &'static str {
+        match self {
+            Renumbering::Bfs => "bfs",
+            Renumbering::Dfs => "dfs",
+            Renumbering::Degree => "degree",
+            Renumbering::ReverseCuthillMcKee => "rcm",
+        }
+    }
 
-#[derive(Debug, Clone, Copy)]
-struct VertexPos {
-    v: Vector2,
-    row: usize,
-    col: usize,
+    /// Permutation `AdjMatrix::permuted` takes. The searches run from
+    /// `start` in `order` to the end, so every vertex gets a number.
+    fn permutation(
+        self,
+        matrix: &AdjMatrix,
+        graph: &AdjList,
+        start: usize,
+        order: Order,
+        costs: &Costs,
+    ) -> Vec<usize> {
+        fn finished<S: Search>(
+            graph: &AdjList,
+            mut step: SearchStep<S>,
+            costs: &Costs,
+        ) -> Vec<usize> {
+            while graph.search_next(&mut step, costs) {}
+            step.renumbering()
+        }
+        let size = matrix.0.len();
+        match self {
+            Renumbering::Bfs => finished(
+                graph,
+                SearchStep::<Bfs>::new(start, size).ordered(order),
+                costs,
+            ),
+            Renumbering::Dfs => finished(
+                graph,
+                SearchStep::<Dfs>::new(start, size).ordered(order),
+                costs,
+            ),
+            Renumbering::Degree => matrix.degree_order(),
+            Renumbering::ReverseCuthillMcKee => matrix.reverse_cuthill_mckee(),
+        }
+    }
+}
+
+// $\mathtt{hue = 200 + 67 \cdot layer}$, so neighbouring layers stand apart
+fn layer_color(layer: usize) -> Color {
+    Color::color_from_hsv((200 + 67 * layer) as f32 % 360.0, 0.8, 0.8)
 }
 
 fn draw_all_vertices<S: Search>(
-    d: &mut RaylibDrawHandle,
+    d: &mut impl RaylibDraw,
     font: &Font,
-    rows: &[usize],
+    vertex_coords: &[VertexPos],
     step: &SearchStep<S>,
-) -> Vec<VertexPos> {
-    fn current_position(index: usize, rows: &[usize]) -> (usize, usize) {
-        let mut cumulative = 0;
-        for (row, &count) in rows.iter().enumerate() {
-            if index < cumulative + count {
-                return (row, index - cumulative);
-            }
-            cumulative += count;
-        }
-        (usize::MAX, usize::MAX)
-    }
-    let winwidth = WIN_WIDTH as f32 * WIN_MARGIN;
-    let winheight = WIN_HEIGHT as f32 * WIN_MARGIN;
-    let vertex_count = rows.iter().sum();
-    let vertex_coords: Vec<VertexPos> = Vec::from_iter((0..vertex_count).map(|i| {
-        let (row, col) = current_position(i, rows);
-
-        let x_offset = (WIN_WIDTH as f32 - winwidth) * 0.5;
-        let y_offset = (WIN_HEIGHT as f32 - winheight) * 0.5;
-        VertexPos {
-            v: Vector2 {
-                x: (winwidth / (DEFAULT_ROWS[row] - 1) as f32 * col as f32) + x_offset,
-                y: (winheight / (DEFAULT_ROWS.len() - 1) as f32 * row as f32) + y_offset,
-            },
-            row,
-            col,
-        }
-    }));
-
-    (0..vertex_count).for_each(|i| {
+    layers: bool,
+) {
+    (0..vertex_coords.len()).for_each(|i| {
         draw::draw_vertex(
             d,
             vertex_coords[i].v,
             &((i + 1).to_string()),
             font,
-            if step.active == i {
-                Color::RED
-            } else if step.visited.iter().any(|(_, to)| *to == i) {
-                Color::PLUM
-            } else {
-                Color::BLACK
+            match step.depth(i) {
+                _ if step.active == i => Color::RED,
+                Some(layer) if layers => layer_color(layer),
+                _ if step.discovered[i].is_some() => Color::PLUM,
+                _ => Color::BLACK,
             },
         );
     });
-
-    vertex_coords
 }
 
-fn draw_all_edges<S: Search>(
-    d: &mut RaylibDrawHandle,
+fn draw_all_edges(
+    d: &mut impl RaylibDraw,
     adj_matrix: &AdjMatrix,
     vertex_coords: &[VertexPos],
     directed: bool,
-    step: &SearchStep<S>,
-    hide_edges: bool,
+    color: impl Fn(usize, usize) -> Color,
 ) {
     for i in 0..vertex_coords.len() {
         let lower = if directed { 0 } else { i };
         for j in lower..vertex_coords.len() {
             let origin = vertex_coords[i];
             let destination = vertex_coords[j];
-            let row_absdiff = (destination.row as i64 - origin.row as i64).abs();
-            let col_absdiff = (destination.col as i64 - origin.col as i64).abs();
 
             if adj_matrix.0[i][j] == 1 {
-                let color = if i != j && step.tree.iter().any(|(from, to)| *from == i && *to == j) {
-                    Color::RED
-                } else if hide_edges {
-                    Color::WHITE.alpha(0.0)
-                } else {
-                    Color::BLACK
-                };
+                let color = color(i, j);
 
                 if i == j {
                     draw::draw_looping_edge(d, origin.v, color);
-                } else if (adj_matrix.0[j][i] == 1 && directed) // symmetric
-                    || (row_absdiff == 0 && col_absdiff > 1) // same row, goes through others
-                    || (col_absdiff == 0 && row_absdiff > 1) // same col, goes through others
-                    || (origin.v.x == destination.v.x) // same x coordinate, yes, still possible
-                    || col_absdiff >= 3
-                // honestly ^ whatever this is
-                {
-                    draw::draw_angled_edge(d, origin.v, destination.v, directed, color);
                 } else {
-                    draw::draw_straight_edge(d, origin.v, destination.v, directed, color);
+                    let min_bend = if directed && adj_matrix.0[j][i] == 1 {
+                        layout::ANTIPARALLEL_BEND
+                    } else {
+                        0.0
+                    };
+                    let bend = layout::edge_bend(vertex_coords, i, j, min_bend);
+                    if bend == 0.0 {
+                        draw::draw_straight_edge(d, origin.v, destination.v, directed, color);
+                    } else {
+                        draw::draw_curved_edge(d, origin.v, destination.v, bend, directed, color);
+                    }
                 }
             }
         }
     }
 }
 
-fn draw_controls(d: &mut RaylibDrawHandle, font: &Font, state: KeyboardKey, hide_edges: bool) {
+/// The graph squeezed into the left half of the window, vertices labelled
+/// with their new numbers.
+fn draw_renumbered(
+    d: &mut impl RaylibDraw,
+    font: &Font,
+    adj_matrix: &AdjMatrix,
+    vertex_coords: &[VertexPos],
+    permutation: &[usize],
+) {
+    for (vertex, position) in vertex_coords.iter().enumerate() {
+        draw::draw_vertex(
+            d,
+            position.v,
+            &(permutation[vertex] + 1).to_string(),
+            font,
+            Color::BLACK,
+        );
+    }
+    draw_all_edges(d, adj_matrix, vertex_coords, true, |_, _| Color::BLACK);
+}
+
+/// Camera that shows what `camera` does at half the size in the left half
+/// of the window, vertically centred. `scale` is the overlay's zoom, from
+/// layout coordinates to screen pixels.
+fn left_half(camera: Camera2D, size: Vector2, scale: f32) -> Camera2D {
+    // $\mathtt{screen = (world - target) \cdot zoom + offset}$, halved
+    Camera2D {
+        offset: camera.offset * 0.5
+            + Vector2 {
+                x: 0.0,
+                y: 0.25 * size.y * scale,
+            },
+        zoom: 0.5 * camera.zoom,
+        ..camera
+    }
+}
+
+/// The renumbered matrix in the right half of the window, a dark cell for
+/// every one, with the bandwidth before and after.
+fn draw_matrix(
+    d: &mut impl RaylibDraw,
+    font: &Font,
+    size: Vector2,
+    renumbering: Renumbering,
+    original: &AdjMatrix,
+    renumbered: &AdjMatrix,
+) {
+    let vertex_count = renumbered.0.len();
+    let origin = Vector2 {
+        x: 0.52 * size.x,
+        y: 0.08 * size.y,
+    };
+    d.draw_text_ex(
+        font,
+        &format!(
+            "{}: bandwidth {} (was {})",
+            renumbering.name(),
+            renumbered.bandwidth(),
+            original.bandwidth()
+        ),
+        origin,
+        LEGEND_FONT_SIZE as f32,
+        0.0,
+        Color::BLACK,
+    );
+    // one more row and column for the vertex numbers
+    let cell = f32::min(0.46 * size.x, 0.72 * size.y) / (vertex_count + 1) as f32;
+    let font_size = f32::min(0.6 * cell, LEGEND_FONT_SIZE as f32);
+    let top_left = origin
+        + Vector2 {
+            x: 0.0,
+            y: 1.5 * LEGEND_FONT_SIZE as f32,
+        };
+    let cell_at = |row: usize, column: usize| Rectangle {
+        x: top_left.x + column as f32 * cell,
+        y: top_left.y + row as f32 * cell,
+        width: cell,
+        height: cell,
+    };
+    for i in 0..vertex_count {
+        let label = (i + 1).to_string();
+        for (row, column) in [(0, i + 1), (i + 1, 0)] {
+            let rect = cell_at(row, column);
+            let text = font.measure_text(&label, font_size, 0.0);
+            let position = Vector2 {
+                x: rect.x + 0.5 * (rect.width - text.x),
+                y: rect.y + 0.5 * (rect.height - text.y),
+            };
+            d.draw_text_ex(font, &label, position, font_size, 0.0, Color::BLACK);
+        }
+        for j in 0..vertex_count {
+            let rect = cell_at(i + 1, j + 1);
+            if renumbered.0[i][j] == 1 {
+                d.draw_rectangle_rec(rect, Color::DARKGRAY);
+            }
+            d.draw_rectangle_lines_ex(rect, 1.0, Color::LIGHTGRAY);
+        }
+    }
+}
+
+fn draw_controls(
+    d: &mut impl RaylibDraw,
+    font: &Font,
+    size: Vector2,
+    current: usize,
+    name: &str,
+    order: Order,
+    style: Style,
+) {
     draw::draw_text(
         d,
         font,
         "<F1>",
         Vector2 {
-            x: 0.05 * WIN_WIDTH as f32,
-            y: 0.01 * WIN_HEIGHT as f32,
+            x: 0.05 * size.x,
+            y: 0.01 * size.y,
         },
-        if state == KeyboardKey::KEY_F1 {
+        if current == 0 {
             Color::RED
         } else {
             Color::BLACK
@@ -134,10 +313,10 @@ fn draw_controls(d: &mut RaylibDrawHandle, font: &Font, state: KeyboardKey, hide
         font,
         "<F2>",
         Vector2 {
-            x: 0.47 * WIN_WIDTH as f32,
-            y: 0.01 * WIN_HEIGHT as f32,
+            x: 0.47 * size.x,
+            y: 0.01 * size.y,
         },
-        if state == KeyboardKey::KEY_F2 {
+        if current == 1 {
             Color::RED
         } else {
             Color::BLACK
@@ -148,96 +327,615 @@ fn draw_controls(d: &mut RaylibDrawHandle, font: &Font, state: KeyboardKey, hide
         font,
         "<F3> Hide edges",
         Vector2 {
-            x: 0.68 * WIN_WIDTH as f32,
-            y: 0.01 * WIN_HEIGHT as f32,
+            x: 0.68 * size.x,
+            y: 0.01 * size.y,
+        },
+        if style.hide_edges {
+            Color::RED
+        } else {
+            Color::BLACK
+        },
+    );
+    let classes = [
+        EdgeClass::Tree,
+        EdgeClass::Back,
+        EdgeClass::Forward,
+        EdgeClass::Cross,
+    ]
+    .map(|class| (class.name().to_string(), edge_color(class)));
+    let hints = [
+        (format!("<F7> {} order", order), Color::BLACK),
+        (
+            String::from("<F8> Layers"),
+            if style.layers {
+                Color::RED
+            } else {
+                Color::BLACK
+            },
+        ),
+        (
+            format!(
+                "<F9> {}",
+                style
+                    .renumbering
+                    .map_or("Renumber", |renumbering| renumbering.name())
+            ),
+            if style.renumbering.is_some() {
+                Color::RED
+            } else {
+                Color::BLACK
+            },
+        ),
+        (String::from("<Click> Start"), Color::BLACK),
+        (String::from("<Right> Goal"), Color::BLACK),
+    ];
+    for (row, (text, color)) in classes.iter().chain(&hints).enumerate() {
+        d.draw_text_ex(
+            font,
+            text,
+            Vector2 {
+                x: 0.01 * size.x,
+                y: 0.08 * size.y + (row as i32 * LEGEND_FONT_SIZE) as f32,
+            },
+            LEGEND_FONT_SIZE as f32,
+            0.0,
+            *color,
+        );
+    }
+    draw::draw_text(
+        d,
+        font,
+        &format!("<F4> {}", if current > 1 { name } else { "More" }),
+        Vector2 {
+            x: 0.02 * size.x,
+            y: 0.94 * size.y,
+        },
+        if current > 1 {
+            Color::RED
+        } else {
+            Color::BLACK
         },
-        if hide_edges { Color::RED } else { Color::BLACK },
     );
     draw::draw_text(
         d,
         font,
         "<Space> Step",
         Vector2 {
-            x: 0.37 * WIN_WIDTH as f32,
-            y: 0.94 * WIN_HEIGHT as f32,
+            x: 0.6 * size.x,
+            y: 0.94 * size.y,
         },
         Color::BLACK,
     );
 }
 
-fn print_new_order<S: Search>(step: &SearchStep<S>) {
+fn print_new_order(events: impl IntoIterator<Item = Event>) {
+    let mut classes: Vec<(EdgeClass, usize)> = Vec::new();
     println!("New vertex order:");
-    step.visited
+    let mut order = 0;
+    for event in events {
+        if let Event::Deepen(limit) = event {
+            order = 0;
+            print!("\nDepth limit {}: ", limit);
+        } else if let Event::Discover(vertex) = event {
+            order += 1;
+            print!("{}->{} ", vertex + 1, order);
+        } else if let Some((_, class)) = event.edge_class() {
+            match classes.iter_mut().find(|(seen, _)| *seen == class) {
+                Some((_, count)) => *count += 1,
+                None => classes.push((class, 1)),
+            }
+        }
+    }
+    println!("\n");
+    for (class, count) in classes {
+        println!("{} edges: {}", class.name(), count);
+    }
+    println!();
+}
+
+/// `old->new` for every vertex, counting from 1.
+fn format_permutation(permutation: &[usize]) -> String {
+    permutation
         .iter()
         .enumerate()
-        .map(|(index, (_, to))| format!("{}->{}", to + 1, index + 1))
-        .for_each(|s| print!("{} ", s));
+        .map(|(old, new)| format!("{}->{}", old + 1, new + 1))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// `lab2-5 compare a.txt b.txt`: whether two graphs <F5> saved are the same
+/// up to vertex numbers, and the renumbering if so. Exits with 1 if they
+/// are not, 2 if they could not be read.
+fn compare(paths: &[String]) {
+    let [first, second] = paths else {
+        eprintln!("usage: lab2-5 compare <graph file> <graph file>");
+        std::process::exit(2);
+    };
+    let load = |path: &String| {
+        AdjMatrix::load(path).unwrap_or_else(|err| {
+            eprintln!("Could not read {}: {}", path, err);
+            std::process::exit(2);
+        })
+    };
+    let (a, b) = (load(first), load(second));
+    for (path, matrix) in [(first, &a), (second, &b)] {
+        println!(
+            "{}: {} vertices, {} edges, canonical hash {:016x}",
+            path,
+            matrix.0.len(),
+            matrix.edges().len(),
+            matrix.canonical_hash()
+        );
+    }
+    match a.isomorphism(&b) {
+        Some(permutation) => println!("Isomorphic: {}", format_permutation(&permutation)),
+        None => {
+            println!("Not isomorphic");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// The tree as a parent array, `-` for roots and undiscovered vertices.
+fn print_parents<S: Search>(step: &SearchStep<S>) {
+    println!("Parents:");
+    for (vertex, parent) in step.parent.iter().enumerate() {
+        match parent {
+            Some(parent) if *parent != vertex => print!("{}->{} ", vertex + 1, parent + 1),
+            _ => print!("{}->- ", vertex + 1),
+        }
+    }
     println!("\n");
 }
 
+/// Tooltip title first, then the details for the side panel.
+fn inspect<S: Search>(adj_matrix: &AdjMatrix, step: &SearchStep<S>, hover: Hover) -> Vec<String> {
+    let or_dash = |value: Option<usize>| value.map_or(String::from("-"), |v| v.to_string());
+    match hover {
+        Hover::Vertex(vertex) => vec![
+            if step.goal == Some(vertex) {
+                format!("Vertex {} (goal)", vertex + 1)
+            } else {
+                format!("Vertex {}", vertex + 1)
+            },
+            format!(
+                "In: {}",
+                adj_matrix.0.iter().filter(|row| row[vertex] == 1).count()
+            ),
+            format!(
+                "Out: {}",
+                adj_matrix.0[vertex].iter().filter(|e| **e == 1).count()
+            ),
+            format!(
+                "{} order: {}",
+                S::NAME,
+                or_dash(step.discovery_order(vertex))
+            ),
+            format!("Depth: {}", or_dash(step.depth(vertex))),
+            format!(
+                "Path: {}",
+                step.path_to(vertex).map_or(String::from("-"), |path| path
+                    .iter()
+                    .map(|vertex| (vertex + 1).to_string())
+                    .collect::<Vec<_>>()
+                    .join(" -> "))
+            ),
+            format!(
+                "Discovered/finished: {}/{}",
+                or_dash(step.discovered[vertex]),
+                or_dash(step.finished[vertex])
+            ),
+            format!(
+                "Cost: {}",
+                if step.cost[vertex].is_finite() {
+                    format!("{:.0}", step.cost[vertex])
+                } else {
+                    String::from("-")
+                }
+            ),
+        ],
+        Hover::Edge(from, to) => vec![
+            format!("Edge {} -> {}", from + 1, to + 1),
+            format!(
+                "{} edge: {}",
+                S::NAME,
+                step.edge_class(from, to).map_or("-", |class| class.name())
+            ),
+        ],
+    }
+}
+
+/// Tree arrangement lays out the search tree built so far, rooted at the
+/// vertices the search started from; the rest use every edge.
+fn arrange<S: Search>(
+    arrangement: Arrangement,
+    rows: &[usize],
+    matrix: &AdjMatrix,
+    step: &SearchStep<S>,
+    size: Vector2,
+) -> Vec<VertexPos> {
+    if arrangement == Arrangement::Tree {
+        let (roots, edges): (Vec<_>, Vec<_>) = step.tree.iter().partition(|(from, to)| from == to);
+        let roots: Vec<usize> = roots.iter().map(|(root, _)| *root).collect();
+        layout::arrange(arrangement, rows, &edges, &roots, size)
+    } else {
+        layout::arrange(arrangement, rows, &matrix.edges(), &[], size)
+    }
+}
+
+type Canvas<'a, 'b> = RaylibMode2D<'a, RaylibDrawHandle<'b>>;
+
+/// A recorded search of any strategy, so the window can switch between
+/// them without knowing which one it shows.
+trait Run {
+    fn name(&self) -> &'static str;
+    fn scrub(&mut self, rl: &RaylibHandle, view: &View) -> bool;
+    /// Handles the timeline keys and steps the search when they ask for
+    /// it, printing the result the one time it finishes. Returns whether
+    /// it stepped.
+    fn update(
+        &mut self,
+        rl: &RaylibHandle,
+        matrix: &AdjMatrix,
+        graph: &AdjList,
+        costs: &Costs,
+    ) -> bool;
+    fn arrange(
+        &self,
+        arrangement: Arrangement,
+        rows: &[usize],
+        matrix: &AdjMatrix,
+        size: Vector2,
+    ) -> Vec<VertexPos>;
+    fn inspect(&self, matrix: &AdjMatrix, hover: Hover) -> Vec<String>;
+    fn draw(
+        &self,
+        d: &mut Canvas,
+        font: &Font,
+        matrix: &AdjMatrix,
+        vertex_coords: &[VertexPos],
+        style: Style,
+    );
+    fn draw_timeline(&self, d: &mut Canvas, font: &Font, size: Vector2);
+}
+
+impl<S: Search + Clone + PartialEq> Run for Timeline<SearchStep<S>> {
+    fn name(&self) -> &'static str {
+        S::NAME
+    }
+
+    fn scrub(&mut self, rl: &RaylibHandle, view: &View) -> bool {
+        Timeline::scrub(self, rl, view.overlay(), view.size)
+    }
+
+    fn update(
+        &mut self,
+        rl: &RaylibHandle,
+        matrix: &AdjMatrix,
+        graph: &AdjList,
+        costs: &Costs,
+    ) -> bool {
+        if !self.tick(rl) {
+            return false;
+        }
+        self.forward(|step| {
+            if graph.search_next(step, costs) {
+                return true;
+            }
+            print_new_order(graph.traverse(step.clone(), *costs));
+            println!(
+                "Renumbered graph:\n{}",
+                matrix.permuted(&step.renumbering())
+            );
+
+            let tree: AdjMatrix = (&*step).into();
+            println!("{} tree:\n{}", S::NAME, tree);
+            print_parents(step);
+            false
+        });
+        true
+    }
+
+    fn arrange(
+        &self,
+        arrangement: Arrangement,
+        rows: &[usize],
+        matrix: &AdjMatrix,
+        size: Vector2,
+    ) -> Vec<VertexPos> {
+        arrange(arrangement, rows, matrix, self.current(), size)
+    }
+
+    fn inspect(&self, matrix: &AdjMatrix, hover: Hover) -> Vec<String> {
+        inspect(matrix, self.current(), hover)
+    }
+
+    fn draw(
+        &self,
+        d: &mut Canvas,
+        font: &Font,
+        matrix: &AdjMatrix,
+        vertex_coords: &[VertexPos],
+        style: Style,
+    ) {
+        let step = self.current();
+        draw_all_vertices(d, font, vertex_coords, step, style.layers);
+        draw_all_edges(d, matrix, vertex_coords, true, |i, j| {
+            match step.edge_class(i, j) {
+                Some(EdgeClass::Tree) => edge_color(EdgeClass::Tree),
+                _ if style.hide_edges => Color::WHITE.alpha(0.0),
+                Some(class) => edge_color(class),
+                None => Color::BLACK,
+            }
+        });
+    }
+
+    fn draw_timeline(&self, d: &mut Canvas, font: &Font, size: Vector2) {
+        Timeline::draw(self, d, font, size);
+    }
+}
+
+/// BFS and DFS first, then the searches <F4> cycles through, which look
+/// for `goal`.
+fn all_searches(start: usize, goal: usize, size: usize, order: Order) -> Vec<Box<dyn Run>> {
+    fn run<S: Search + Clone + PartialEq + 'static>(
+        step: SearchStep<S>,
+        order: Order,
+    ) -> Box<dyn Run> {
+        Box::new(Timeline::new(step.ordered(order)))
+    }
+    vec![
+        run(SearchStep::<Bfs>::new(start, size), order),
+        run(SearchStep::<Dfs>::new(start, size), order),
+        run(SearchStep::<Iddfs>::towards(start, goal, size), order),
+        run(SearchStep::<UniformCost>::towards(start, goal, size), order),
+        run(SearchStep::<Greedy>::towards(start, goal, size), order),
+        run(SearchStep::<AStar>::towards(start, goal, size), order),
+        run(
+            SearchStep::<Bidirectional>::towards(start, goal, size),
+            order,
+        ),
+    ]
+}
+
 fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).is_some_and(|command| command == "compare") {
+        compare(&args[2..]);
+        return;
+    }
+    let variant = Variant::from_args();
+    let rows = variant.rows();
+
     let (mut rl, thread) = raylib::init()
         .size(WIN_WIDTH, WIN_HEIGHT)
         .log_level(TraceLogLevel::LOG_WARNING)
         .title("ASD Lab 2.5")
+        .resizable()
         .build();
+    rl.set_window_min_size(WIN_WIDTH / 2, WIN_HEIGHT / 2);
 
     let font = rl.load_font(&thread, "FiraCode-Regular.ttf").unwrap();
 
-    let matrix = AdjMatrix::generate(K);
-    let start_vertex = matrix
+    let matrix = AdjMatrix::generate(
+        variant.vertex_count(),
+        variant.seed(),
+        variant.k(Lab::Traversal),
+    );
+    // a click on a vertex starts the searches over from it, a right click
+    // gives them another goal
+    let mut start_vertex = matrix
         .0
         .iter()
         .position(|row| row.iter().all(|v| *v != 0))
         .unwrap_or(0);
-    let mut bfs = SearchStep::new(start_vertex, VERTEX_COUNT);
-    let mut dfs = SearchStep::new(start_vertex, VERTEX_COUNT);
-    let mut state = KeyboardKey::KEY_F1;
-    let mut hide_edges = false;
+    let mut goal = if start_vertex + 1 == variant.vertex_count() {
+        0
+    } else {
+        variant.vertex_count() - 1
+    };
+    // a second argument seeds the random neighbour order
+    let seed = std::env::args().nth(2).map_or(variant.seed(), |arg| {
+        arg.parse().expect("seed must be a number, e.g. 42")
+    });
+    let mut order = Order::Index;
+    let mut searches = all_searches(start_vertex, goal, variant.vertex_count(), order);
+    let mut click = None;
+    // print the renumbering <F9> picks once
+    let mut renumbered = false;
+    // the renumbering shown, its permutation and the graph renumbered by
+    // it, worked out again only once what they were worked out from changes
+    let mut renumbering: Option<(Renumbering, Vec<usize>, AdjMatrix)> = None;
+    let mut renumbered_from = None;
+    // <F5> saves the graph shown too, once the renumbering is known
+    let mut save_graph = false;
+    let mut current = 0;
+    let mut style = Style {
+        hide_edges: false,
+        layers: false,
+        renumbering: None,
+    };
+    let layout_file = layout::file_name(&variant, None);
+    let mut view = View::new(&rl);
+    let mut vertex_coords = layout::load_or_rows(&layout_file, &rows, view.size);
+    let mut drag = layout::Drag::new();
+    let mut arrangement = Arrangement::Rows;
 
+    println!("Variant: {}", variant);
     println!("Graph:\n{}", matrix);
+    println!("Canonical hash: {:016x}\n", matrix.canonical_hash());
+    let graph = AdjList::from(&matrix);
 
     while !rl.window_should_close() {
+        if let Some(old_size) = view.update(&rl) {
+            layout::rescale(&mut vertex_coords, old_size, view.size);
+        }
+        let previous = current;
         if rl.is_key_pressed(KeyboardKey::KEY_F1) {
-            state = KeyboardKey::KEY_F1;
+            current = 0;
         } else if rl.is_key_pressed(KeyboardKey::KEY_F2) {
-            state = KeyboardKey::KEY_F2;
+            current = 1;
         } else if rl.is_key_pressed(KeyboardKey::KEY_F3) {
-            hide_edges = !hide_edges;
+            style.hide_edges = !style.hide_edges;
+        } else if rl.is_key_pressed(KeyboardKey::KEY_F4) {
+            current = if current + 1 < searches.len() && current > 1 {
+                current + 1
+            } else {
+                2
+            };
+        } else if rl.is_key_pressed(KeyboardKey::KEY_F5) {
+            match layout::save(&layout_file, &vertex_coords) {
+                Ok(()) => println!("Layout saved to {}", layout_file),
+                Err(err) => eprintln!("Could not save {}: {}", layout_file, err),
+            }
+            save_graph = true;
+        } else if rl.is_key_pressed(KeyboardKey::KEY_F6) {
+            arrangement = arrangement.next();
+            println!("Arrangement: {:?}", arrangement);
+            vertex_coords = searches[current].arrange(arrangement, &rows, &matrix, view.size);
+        } else if rl.is_key_pressed(KeyboardKey::KEY_F8) {
+            style.layers = !style.layers;
+        } else if rl.is_key_pressed(KeyboardKey::KEY_F7) {
+            order = order.next(seed);
+            println!("Neighbour order: {}", order);
+            searches = all_searches(start_vertex, goal, variant.vertex_count(), order);
+        } else if rl.is_key_pressed(KeyboardKey::KEY_F9) {
+            style.renumbering = Renumbering::next(style.renumbering);
+            renumbered = true;
+        }
+        // the renumbered graph gets the left half of the window
+        let camera = match style.renumbering {
+            Some(_) => left_half(view.camera, view.size, view.overlay().zoom),
+            None => view.camera,
+        };
+        let mouse = match style.renumbering {
+            Some(_) => rl.get_screen_to_world2D(rl.get_mouse_position(), camera),
+            None => view.mouse(&rl),
+        };
+        if rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_RIGHT)
+            && let Some(vertex) = layout::vertex_at(&vertex_coords, mouse)
+        {
+            goal = vertex;
+            println!("Goal: {}", goal + 1);
+            searches = all_searches(start_vertex, goal, variant.vertex_count(), order);
         }
 
-        if rl.is_key_pressed(KeyboardKey::KEY_SPACE) {
-            match state {
-                KeyboardKey::KEY_F1 => {
-                    if !matrix.search_next::<Bfs>(&mut bfs) {
-                        print_new_order(&bfs);
-
-                        let matrix: AdjMatrix = (&bfs).into();
-                        println!("BFS tree:\n{}", matrix)
-                    }
-                }
-                KeyboardKey::KEY_F2 => {
-                    if !matrix.search_next::<Dfs>(&mut dfs) {
-                        print_new_order(&dfs);
+        let scrubbing = searches[current].scrub(&rl, &view);
+        if !scrubbing {
+            // a press and release on the same spot is a click, anything
+            // else drags the vertex
+            if rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT) {
+                click = layout::vertex_at(&vertex_coords, mouse)
+                    .map(|vertex| (vertex, rl.get_mouse_position()));
+            } else if rl.is_mouse_button_released(MouseButton::MOUSE_BUTTON_LEFT)
+                && let Some((vertex, pressed_at)) = click.take()
+                && pressed_at.distance_to(rl.get_mouse_position()) <= CLICK_SLOP
+            {
+                start_vertex = vertex;
+                println!("Start: {}", start_vertex + 1);
+                searches = all_searches(start_vertex, goal, variant.vertex_count(), order);
+            }
+            drag.update(&rl, mouse, &mut vertex_coords);
+        }
 
-                        let matrix: AdjMatrix = (&dfs).into();
-                        println!("DFS tree:\n{}", matrix);
-                    }
-                }
-                _ => {}
+        // edges are as long as they are drawn, which also keeps the
+        // straight-line heuristic from overestimating
+        let distance =
+            |from: usize, to: usize| vertex_coords[from].v.distance_to(vertex_coords[to].v);
+        let costs = Costs {
+            length: &distance,
+            heuristic: &distance,
+        };
+        let stepped = searches[current].update(&rl, &matrix, &graph, &costs);
+        // edge lengths, which the weight order goes by, come from the layout
+        let from = (
+            style.renumbering,
+            start_vertex,
+            order,
+            vertex_coords
+                .iter()
+                .map(|position| position.v)
+                .collect::<Vec<_>>(),
+        );
+        if renumbered_from.as_ref() != Some(&from) {
+            renumbering = style.renumbering.map(|renumbering| {
+                let permutation =
+                    renumbering.permutation(&matrix, &graph, start_vertex, order, &costs);
+                let renumbered = matrix.permuted(&permutation);
+                (renumbering, permutation, renumbered)
+            });
+            renumbered_from = Some(from);
+        }
+        if let Some((renumbering, permutation, shown)) = &renumbering
+            && std::mem::take(&mut renumbered)
+        {
+            println!(
+                "{}: {}",
+                renumbering.name(),
+                format_permutation(permutation)
+            );
+            println!("Renumbered graph:\n{}", shown);
+            println!("Canonical hash: {:016x}\n", shown.canonical_hash());
+        }
+        if std::mem::take(&mut save_graph) {
+            let (graph_file, shown) = match &renumbering {
+                Some((renumbering, _, shown)) => (
+                    format!("graph_{}_{}.txt", variant, renumbering.file_suffix()),
+                    shown.clone(),
+                ),
+                None => (format!("graph_{}.txt", variant), matrix.clone()),
+            };
+            match shown.save(&graph_file) {
+                Ok(()) => println!("Graph saved to {}", graph_file),
+                Err(err) => eprintln!("Could not save {}: {}", graph_file, err),
             }
         }
+        // the tree changes with every step, keep its layout in sync
+        if arrangement == Arrangement::Tree && (stepped || scrubbing || current != previous) {
+            vertex_coords = searches[current].arrange(arrangement, &rows, &matrix, view.size);
+        }
+        let search = &searches[current];
+        // old numbers would only confuse the renumbered view
+        let hover = layout::hover(&vertex_coords, &matrix.edges(), true, mouse)
+            .filter(|_| renumbering.is_none());
+        let inspection = hover.map(|hover| search.inspect(&matrix, hover));
         let mut d = rl.begin_drawing(&thread);
 
         d.clear_background(Color::WHITE);
-        draw_controls(&mut d, &font, state, hide_edges);
-
-        if state == KeyboardKey::KEY_F1 {
-            let vertex_coords = draw_all_vertices(&mut d, &font, DEFAULT_ROWS, &bfs);
-            draw_all_edges(&mut d, &matrix, &vertex_coords, true, &bfs, hide_edges);
-        } else if state == KeyboardKey::KEY_F2 {
-            let vertex_coords = draw_all_vertices(&mut d, &font, DEFAULT_ROWS, &dfs);
-            draw_all_edges(&mut d, &matrix, &vertex_coords, true, &dfs, hide_edges);
+        {
+            let mut d = d.begin_mode2D(camera);
+            match &renumbering {
+                Some((_, permutation, _)) => {
+                    draw_renumbered(&mut d, &font, &matrix, &vertex_coords, permutation)
+                }
+                None => search.draw(&mut d, &font, &matrix, &vertex_coords, style),
+            }
+            if let Some(lines) = &inspection {
+                draw::draw_panel(&mut d, &font, &lines[..1], mouse + TOOLTIP_OFFSET);
+            }
+        }
+
+        let mut d = d.begin_mode2D(view.overlay());
+        draw_controls(
+            &mut d,
+            &font,
+            view.size,
+            current,
+            search.name(),
+            order,
+            style,
+        );
+        search.draw_timeline(&mut d, &font, view.size);
+        if let Some((renumbering, _, shown)) = &renumbering {
+            draw_matrix(&mut d, &font, view.size, *renumbering, &matrix, shown);
+        }
+        if let Some(lines) = &inspection {
+            let panel = draw::panel_size(&font, lines);
+            let position = Vector2 {
+                x: 0.99 * view.size.x - panel.x,
+                y: 0.08 * view.size.y,
+            };
+            draw::draw_panel(&mut d, &font, lines, position);
         }
     }
 }
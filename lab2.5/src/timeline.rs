@@ -0,0 +1,192 @@
+use raylib::prelude::*;
+
+const SPEED_MIN: f32 = 0.5;
+const SPEED_MAX: f32 = 32.0;
+const SLIDER_HEIGHT: f32 = 8.0;
+const SLIDER_GRAB: f32 = 10.0; // extra height around the bar that still grabs it
+const FONT_SIZE: i32 = 20;
+
+/// Every state an algorithm has been in, so it can be replayed both ways:
+/// - Space or Right steps forward, computing a new state past the last one,
+/// - Left steps back,
+/// - clicking or dragging the slider jumps to any recorded step,
+/// - P toggles autoplay, `+`/`-` double or halve its speed.
+pub struct Timeline<T> {
+    states: Vec<T>,
+    position: usize,
+    playing: bool,
+    speed: f32, // steps per second
+    elapsed: f32,
+    scrubbing: bool,
+    /// Set once stepping past the last state has returned `false`.
+    done: bool,
+}
+
+/// Bar of the slider in overlay coordinates, for a window of `size`.
+fn slider(size: Vector2) -> Rectangle {
+    Rectangle {
+        x: 0.1 * size.x,
+        y: 0.89 * size.y,
+        width: 0.8 * size.x,
+        height: SLIDER_HEIGHT,
+    }
+}
+
+impl<T: Clone + PartialEq> Timeline<T> {
+    pub fn new(initial: T) -> Self {
+        Timeline {
+            states: vec![initial],
+            position: 0,
+            playing: false,
+            speed: 2.0,
+            elapsed: 0.0,
+            scrubbing: false,
+            done: false,
+        }
+    }
+
+    pub fn current(&self) -> &T {
+        &self.states[self.position]
+    }
+
+    /// Moves one step forward: replays a recorded state, or past the last one
+    /// records the state `step` leaves behind. Returns what `step` returned,
+    /// `true` for replayed states. Once `step` has returned `false` it is
+    /// never called again, so whatever it does at the end happens once.
+    /// Autoplay stops once nothing changes.
+    pub fn forward(&mut self, step: impl FnOnce(&mut T) -> bool) -> bool {
+        if self.position + 1 < self.states.len() {
+            self.position += 1;
+            return true;
+        }
+        if self.done {
+            self.playing = false;
+            return false;
+        }
+        let mut next = self.current().clone();
+        let result = step(&mut next);
+        self.done = !result;
+        if next == *self.current() {
+            self.playing = false;
+        } else {
+            self.states.push(next);
+            self.position += 1;
+        }
+        result
+    }
+
+    pub fn back(&mut self) {
+        self.position = self.position.saturating_sub(1);
+    }
+
+    /// Handles the slider, returns `true` while it has the mouse so the
+    /// caller can ignore the click. `overlay` is the camera the slider is
+    /// drawn with, `size` the window size in its coordinates.
+    pub fn scrub(&mut self, rl: &RaylibHandle, overlay: Camera2D, size: Vector2) -> bool {
+        let bar = slider(size);
+        let mouse = rl.get_screen_to_world2D(rl.get_mouse_position(), overlay);
+        if rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT) {
+            self.scrubbing = mouse.x >= bar.x
+                && mouse.x <= bar.x + bar.width
+                && (mouse.y - bar.y - 0.5 * bar.height).abs() <= 0.5 * bar.height + SLIDER_GRAB;
+        } else if rl.is_mouse_button_released(MouseButton::MOUSE_BUTTON_LEFT) {
+            self.scrubbing = false;
+        }
+        if self.scrubbing {
+            let fraction = ((mouse.x - bar.x) / bar.width).clamp(0.0, 1.0);
+            self.position = (fraction * (self.states.len() - 1) as f32).round() as usize;
+            self.playing = false;
+        }
+        self.scrubbing
+    }
+
+    /// Handles the keys; returns `true` when the caller should step forward,
+    /// either by hand or because autoplay is due.
+    pub fn tick(&mut self, rl: &RaylibHandle) -> bool {
+        if rl.is_key_pressed(KeyboardKey::KEY_LEFT) {
+            self.back();
+            self.playing = false;
+        } else if rl.is_key_pressed(KeyboardKey::KEY_P) {
+            self.playing = !self.playing;
+            self.elapsed = 0.0;
+        } else if rl.is_key_pressed(KeyboardKey::KEY_EQUAL) {
+            self.speed = (self.speed * 2.0).min(SPEED_MAX);
+        } else if rl.is_key_pressed(KeyboardKey::KEY_MINUS) {
+            self.speed = (self.speed * 0.5).max(SPEED_MIN);
+        }
+        if rl.is_key_pressed(KeyboardKey::KEY_SPACE) || rl.is_key_pressed(KeyboardKey::KEY_RIGHT) {
+            return true;
+        }
+        if self.playing {
+            self.elapsed += rl.get_frame_time();
+            if self.elapsed >= 1.0 / self.speed {
+                self.elapsed = 0.0;
+                return true;
+            }
+        }
+        false
+    }
+
+    pub fn draw(&self, d: &mut impl RaylibDraw, font: &Font, size: Vector2) {
+        let bar = slider(size);
+        let last = (self.states.len() - 1).max(1);
+        let knob = Vector2 {
+            x: bar.x + bar.width * self.position as f32 / last as f32,
+            y: bar.y + 0.5 * bar.height,
+        };
+        d.draw_rectangle_rec(bar, Color::LIGHTGRAY);
+        d.draw_rectangle_rec(
+            Rectangle {
+                width: knob.x - bar.x,
+                ..bar
+            },
+            Color::GRAY,
+        );
+        d.draw_circle_v(knob, bar.height, Color::DARKGRAY);
+
+        let text = format!(
+            "Step {}/{}  {} x{}  <Left> <P> <+/->",
+            self.position,
+            self.states.len() - 1,
+            if self.playing { "Playing" } else { "Paused" },
+            self.speed
+        );
+        d.draw_text_ex(
+            font,
+            &text,
+            Vector2 {
+                x: bar.x,
+                y: bar.y - 1.5 * FONT_SIZE as f32,
+            },
+            FONT_SIZE as f32,
+            0.0,
+            Color::BLACK,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn step_ends_once() {
+        let mut timeline = Timeline::new(0);
+        let mut calls = 0;
+        let mut count_to_two = |timeline: &mut Timeline<i32>| {
+            timeline.forward(|state| {
+                calls += 1;
+                *state = (*state + 1).min(2);
+                *state < 2
+            })
+        };
+        assert!(count_to_two(&mut timeline));
+        assert!(!count_to_two(&mut timeline));
+        assert!(!count_to_two(&mut timeline));
+        assert_eq!(*timeline.current(), 2);
+        timeline.back();
+        assert!(count_to_two(&mut timeline));
+        assert!(!count_to_two(&mut timeline));
+        assert_eq!(calls, 2);
+    }
+}
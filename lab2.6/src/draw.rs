@@ -2,17 +2,21 @@ use std::f32::consts::PI;
 
 use raylib::prelude::*;
 
+use crate::layout;
+
 const VERTEX_FONT_SIZE: i32 = 32;
 const WEIGHT_FONT_SIZE: i32 = 24;
 
-const VERTEX_RADIUS: f32 = 20.0;
+const PANEL_FONT_SIZE: i32 = 20;
+const PANEL_PADDING: f32 = 8.0;
+
+pub const VERTEX_RADIUS: f32 = 20.0;
 const VERTEX_WIDTH: u32 = 3;
 
-const EDGE_BASE_ANGLE: f32 = 0.05 * PI;
 const WEIGHT_TEXT_OFFSET: f32 = 0.35; // 0.0..=0.5
 
 pub fn draw_text_pro(
-    d: &mut RaylibDrawHandle,
+    d: &mut impl RaylibDraw,
     font: &Font,
     text: &str,
     position: Vector2,
@@ -36,7 +40,7 @@ pub fn draw_text_pro(
 }
 
 pub fn draw_text(
-    d: &mut RaylibDrawHandle,
+    d: &mut impl RaylibDraw,
     font: &Font,
     text: &str,
     position: Vector2,
@@ -47,7 +51,7 @@ pub fn draw_text(
 }
 
 pub fn draw_vertex(
-    d: &mut RaylibDrawHandle,
+    d: &mut impl RaylibDraw,
     center: Vector2,
     weight: &str,
     font: &Font,
@@ -77,7 +81,7 @@ pub fn draw_vertex(
     }
 }
 
-fn draw_arrowhead(d: &mut RaylibDrawHandle, position: Vector2, direction: Vector2, color: Color) {
+fn draw_arrowhead(d: &mut impl RaylibDraw, position: Vector2, direction: Vector2, color: Color) {
     const ARROWHEAD_LEN: f32 = VERTEX_RADIUS * 0.5;
     const ARROWHEAD_ANGLE: f32 = PI / 6.0;
     d.draw_line_v(
@@ -93,23 +97,24 @@ fn draw_arrowhead(d: &mut RaylibDrawHandle, position: Vector2, direction: Vector
 }
 
 pub fn draw_edge_weight(
-    d: &mut RaylibDrawHandle,
+    d: &mut impl RaylibDraw,
     center_from: Vector2,
     center_to: Vector2,
     weight: &str,
     font: &Font,
     color: Color,
-    angled: bool,
+    bend: f32,
 ) {
-    let direction = center_to - center_from;
-    let normal = direction.normalized().rotated(0.5 * PI);
-    let offset = direction * WEIGHT_TEXT_OFFSET;
-
-    let mut text_pos = center_from + offset;
-    let mut angle = Vector2 { x: 1.0, y: 0.0 }.angle_to(direction);
-    if angled {
-        text_pos += normal * (offset.length() * EDGE_BASE_ANGLE.tan());
-        angle = Vector2 { x: 1.0, y: 0.0 }.angle_to(text_pos - center_from);
+    let control = layout::bezier_control(center_from, center_to, bend);
+    let text_pos = layout::bezier_point(center_from, control, center_to, WEIGHT_TEXT_OFFSET);
+    let tangent = layout::bezier_tangent(center_from, control, center_to, WEIGHT_TEXT_OFFSET);
+
+    // along the edge, but never upside down
+    let mut angle = tangent.y.atan2(tangent.x);
+    if angle > 0.5 * PI {
+        angle -= PI;
+    } else if angle < -0.5 * PI {
+        angle += PI;
     }
     draw_text_pro(
         d,
@@ -123,7 +128,7 @@ pub fn draw_edge_weight(
 }
 
 pub fn draw_straight_edge(
-    d: &mut RaylibDrawHandle,
+    d: &mut impl RaylibDraw,
     center_from: Vector2,
     center_to: Vector2,
     directional: bool,
@@ -139,30 +144,34 @@ pub fn draw_straight_edge(
     }
 }
 
-pub fn draw_angled_edge(
-    d: &mut RaylibDrawHandle,
+/// Quadratic Bézier edge, see `layout::edge_bend` for how `bend` is picked.
+pub fn draw_curved_edge(
+    d: &mut impl RaylibDraw,
     center_from: Vector2,
     center_to: Vector2,
+    bend: f32,
     directed: bool,
     color: Color,
 ) {
-    let direction = (center_to - center_from).normalized();
-    let from = center_from + direction * VERTEX_RADIUS;
-    let to = center_to - direction * VERTEX_RADIUS;
-
-    let vector = to - from;
-    let vector_middle = vector * 0.5;
-    let mid_offset = vector_middle.length() * EDGE_BASE_ANGLE.tan();
+    let control = layout::bezier_control(center_from, center_to, bend);
+    let from = center_from + (control - center_from).normalized() * VERTEX_RADIUS;
+    let to = center_to + (control - center_to).normalized() * VERTEX_RADIUS;
 
-    let midpoint = from + vector_middle + direction.rotated(0.5 * PI) * mid_offset;
-    d.draw_line_v(from, midpoint, color);
-    d.draw_line_v(midpoint, to, color);
+    let points: [Vector2; layout::CURVE_SEGMENTS + 1] = std::array::from_fn(|i| {
+        layout::bezier_point(from, control, to, i as f32 / layout::CURVE_SEGMENTS as f32)
+    });
+    d.draw_line_strip(&points, color);
     if directed {
-        draw_arrowhead(d, to, (to - midpoint).normalized(), color);
+        draw_arrowhead(
+            d,
+            to,
+            layout::bezier_tangent(from, control, to, 1.0).normalized(),
+            color,
+        );
     }
 }
 
-pub fn draw_looping_edge(d: &mut RaylibDrawHandle, center: Vector2, color: Color) {
+pub fn draw_looping_edge(d: &mut impl RaylibDraw, center: Vector2, color: Color) {
     const POINTS: usize = 16;
     const RADIUS: f32 = 12.0;
     const START_ANGLE: f32 = -0.9 * PI;
@@ -185,3 +194,38 @@ pub fn draw_looping_edge(d: &mut RaylibDrawHandle, center: Vector2, color: Color
     let direction = (last_point - points[POINTS - 4]).normalized();
     draw_arrowhead(d, last_point, direction, color);
 }
+
+/// Size of the box `draw_panel` draws around `lines`.
+pub fn panel_size(font: &Font, lines: &[String]) -> Vector2 {
+    let width = lines
+        .iter()
+        .map(|line| font.measure_text(line, PANEL_FONT_SIZE as f32, 0.0).x)
+        .fold(0.0, f32::max);
+    Vector2 {
+        x: width + 2.0 * PANEL_PADDING,
+        y: lines.len() as f32 * PANEL_FONT_SIZE as f32 + 2.0 * PANEL_PADDING,
+    }
+}
+
+/// Lines of text on a light box with its top left corner at `position`.
+pub fn draw_panel(d: &mut impl RaylibDraw, font: &Font, lines: &[String], position: Vector2) {
+    d.draw_rectangle_v(
+        position,
+        panel_size(font, lines),
+        Color::LIGHTGRAY.alpha(0.9),
+    );
+    lines.iter().enumerate().for_each(|(i, line)| {
+        let offset = Vector2 {
+            x: PANEL_PADDING,
+            y: PANEL_PADDING + i as f32 * PANEL_FONT_SIZE as f32,
+        };
+        d.draw_text_ex(
+            font,
+            line,
+            position + offset,
+            PANEL_FONT_SIZE as f32,
+            0.0,
+            Color::BLACK,
+        );
+    });
+}
@@ -0,0 +1,128 @@
+use raylib::prelude::*;
+
+use crate::{
+    draw,
+    graph::Graph,
+    layout::{self, Drag, VertexPos},
+};
+
+const WEIGHT_MAX_DIGITS: usize = 4;
+const PROMPT_FONT_SIZE: i32 = 24;
+
+/// Mouse-driven editing of the graph:
+/// - click on empty space to add a vertex,
+/// - drag a vertex to move it,
+/// - shift-drag from one vertex to another to add an edge, then type its weight,
+/// - right-click a vertex or an edge to delete it.
+pub struct Editor {
+    drag: Drag,
+    edge_from: Option<usize>,
+    pending: Option<(usize, usize, String)>,
+}
+
+impl Editor {
+    pub fn new() -> Self {
+        Editor {
+            drag: Drag::new(),
+            edge_from: None,
+            pending: None,
+        }
+    }
+
+    /// Whether keyboard input currently goes into the weight prompt.
+    pub fn is_typing(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// Applies this frame's input, returns `true` if the graph itself changed.
+    /// `mouse` is the cursor in layout coordinates.
+    pub fn update(
+        &mut self,
+        rl: &mut RaylibHandle,
+        mouse: Vector2,
+        graph: &mut Graph,
+        vertices: &mut Vec<VertexPos>,
+    ) -> bool {
+        if let Some((from, to, weight)) = &mut self.pending {
+            while let Some(c) = rl.get_char_pressed() {
+                if c.is_ascii_digit() && weight.len() < WEIGHT_MAX_DIGITS {
+                    weight.push(c);
+                }
+            }
+            if rl.is_key_pressed(KeyboardKey::KEY_BACKSPACE) {
+                weight.pop();
+            } else if rl.is_key_pressed(KeyboardKey::KEY_ENTER) {
+                // an empty or zero weight cancels the edge
+                let (from, to) = (*from, *to);
+                let weight = weight.parse::<u32>().ok().filter(|w| *w > 0);
+                self.pending = None;
+                if weight.is_some() {
+                    graph.set_edge(from, to, weight);
+                    return true;
+                }
+            }
+            return false;
+        }
+
+        let hovered = layout::vertex_at(vertices, mouse);
+
+        if rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT) {
+            match hovered {
+                Some(vertex) if rl.is_key_down(KeyboardKey::KEY_LEFT_SHIFT) => {
+                    self.edge_from = Some(vertex);
+                }
+                Some(_) => {}
+                None => {
+                    graph.add_vertex();
+                    vertices.push(VertexPos::free(mouse));
+                    return true;
+                }
+            }
+        } else if rl.is_mouse_button_released(MouseButton::MOUSE_BUTTON_LEFT)
+            && let Some(from) = self.edge_from.take()
+            && let Some(to) = hovered
+            && to != from
+        {
+            self.pending = Some((from.min(to), from.max(to), String::new()));
+        }
+        if self.edge_from.is_none() {
+            self.drag.update(rl, mouse, vertices);
+        }
+
+        if rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_RIGHT) {
+            if let Some(vertex) = hovered {
+                graph.remove_vertex(vertex);
+                vertices.remove(vertex);
+                return true;
+            } else if let Some((from, to)) = layout::edge_at(vertices, &graph.edges(), false, mouse)
+            {
+                graph.set_edge(from, to, None);
+                return true;
+            }
+        }
+        false
+    }
+
+    pub fn draw(
+        &self,
+        d: &mut impl RaylibDraw,
+        font: &Font,
+        mouse: Vector2,
+        vertices: &[VertexPos],
+    ) {
+        if let Some(from) = self.edge_from {
+            d.draw_line_v(vertices[from].v, mouse, Color::GRAY);
+        }
+        if let Some((from, to, weight)) = &self.pending {
+            d.draw_line_v(vertices[*from].v, vertices[*to].v, Color::GRAY);
+            draw::draw_text(
+                d,
+                font,
+                &format!("Weight: {}_", weight),
+                (vertices[*from].v + vertices[*to].v) * 0.5,
+                PROMPT_FONT_SIZE as f32,
+                Color::BLUE,
+            );
+        }
+    }
+}
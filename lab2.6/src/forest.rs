@@ -0,0 +1,169 @@
+use raylib::prelude::*;
+
+use crate::graph::UnionFind;
+
+const NODE_RADIUS: f32 = 10.0;
+const LABEL_FONT_SIZE: i32 = 16;
+const RANK_FONT_SIZE: i32 = 12;
+const TITLE_FONT_SIZE: i32 = 20;
+const PADDING: f32 = 8.0;
+// seconds a step's pointer moves take to play out
+pub const ANIMATION_TIME: f32 = 0.6;
+
+/// Node centres for the forest `parent` describes, laid out in `area`:
+/// roots on top, each node over the middle of its children, the leaves
+/// side by side.
+fn positions(parent: &[usize], area: Rectangle) -> Vec<Vector2> {
+    let size = parent.len();
+    let mut children = vec![Vec::new(); size];
+    for vertex in 0..size {
+        if parent[vertex] != vertex {
+            children[parent[vertex]].push(vertex);
+        }
+    }
+
+    // leaf slots and depths, filled in depth first from every root
+    fn place(
+        vertex: usize,
+        depth: usize,
+        children: &[Vec<usize>],
+        next_slot: &mut usize,
+        slots: &mut [(f32, usize)],
+    ) {
+        if children[vertex].is_empty() {
+            slots[vertex] = (*next_slot as f32, depth);
+            *next_slot += 1;
+            return;
+        }
+        for child in &children[vertex] {
+            place(*child, depth + 1, children, next_slot, slots);
+        }
+        let first = slots[children[vertex][0]].0;
+        let last = slots[*children[vertex].last().unwrap()].0;
+        slots[vertex] = (0.5 * (first + last), depth);
+    }
+    let mut slots = vec![(0.0, 0); size];
+    let mut next_slot = 0;
+    for root in (0..size).filter(|vertex| parent[*vertex] == *vertex) {
+        place(root, 0, &children, &mut next_slot, &mut slots);
+    }
+
+    // room above the roots for their ranks
+    let top = area.y + RANK_FONT_SIZE as f32 + NODE_RADIUS;
+    let levels = slots.iter().map(|(_, depth)| *depth).max().unwrap_or(0);
+    let slot_width = area.width / size.max(1) as f32;
+    let level_height = f32::min(
+        4.0 * NODE_RADIUS,
+        (area.y + area.height - NODE_RADIUS - top) / levels.max(1) as f32,
+    );
+    slots
+        .iter()
+        .map(|(slot, depth)| Vector2 {
+            x: area.x + (slot + 0.5) * slot_width,
+            y: top + *depth as f32 * level_height,
+        })
+        .collect()
+}
+
+/// The disjoint-set forest in `area` of the overlay: every vertex with an
+/// arrow to its parent, and its rank over it if it is a root. The pointers
+/// the last step moved swing from their old parent to the new one while
+/// `progress` goes from 0 to 1, union in red and path compression in blue.
+pub fn draw(d: &mut impl RaylibDraw, font: &Font, area: Rectangle, uf: &UnionFind, progress: f32) {
+    d.draw_rectangle_rec(area, Color::LIGHTGRAY.alpha(0.9));
+    d.draw_text_ex(
+        font,
+        "Union-Find (rank)",
+        Vector2 {
+            x: area.x + PADDING,
+            y: area.y + PADDING,
+        },
+        TITLE_FONT_SIZE as f32,
+        0.0,
+        Color::BLACK,
+    );
+    let forest = Rectangle {
+        x: area.x + PADDING,
+        y: area.y + 2.0 * PADDING + TITLE_FONT_SIZE as f32,
+        width: area.width - 2.0 * PADDING,
+        height: area.height - 3.0 * PADDING - TITLE_FONT_SIZE as f32,
+    };
+
+    let parent = uf.parents();
+    let mut old_parent = parent.to_vec();
+    for change in uf.changes().iter().rev() {
+        old_parent[change.vertex] = change.from;
+    }
+    // ease out, so moves settle gently
+    let t = 1.0 - (1.0 - progress.clamp(0.0, 1.0)).powi(2);
+    let before = positions(&old_parent, forest);
+    let after = positions(parent, forest);
+    let position: Vec<Vector2> = before
+        .iter()
+        .zip(&after)
+        .map(|(before, after)| before.lerp(*after, t))
+        .collect();
+
+    for vertex in 0..parent.len() {
+        let moved = uf.changes().iter().find(|change| change.vertex == vertex);
+        let (end, color) = match moved {
+            Some(change) => (
+                position[old_parent[vertex]].lerp(position[parent[vertex]], t),
+                if change.union {
+                    Color::RED
+                } else {
+                    Color::BLUE
+                },
+            ),
+            None => (position[parent[vertex]], Color::DARKGRAY),
+        };
+        if end.distance_to(position[vertex]) > NODE_RADIUS {
+            let direction = (end - position[vertex]).normalized();
+            d.draw_line_ex(
+                position[vertex] + direction * NODE_RADIUS,
+                end - direction * NODE_RADIUS,
+                2.0,
+                color,
+            );
+            d.draw_circle_v(end - direction * NODE_RADIUS, 3.0, color);
+        }
+    }
+
+    for (vertex, center) in position.iter().enumerate() {
+        d.draw_circle_v(*center, NODE_RADIUS, Color::WHITE);
+        d.draw_circle_lines(
+            center.x as i32,
+            center.y as i32,
+            NODE_RADIUS,
+            if parent[vertex] == vertex {
+                Color::BLACK
+            } else {
+                Color::GRAY
+            },
+        );
+        let label = (vertex + 1).to_string();
+        let label_size = font.measure_text(&label, LABEL_FONT_SIZE as f32, 0.0);
+        d.draw_text_ex(
+            font,
+            &label,
+            *center - label_size * 0.5,
+            LABEL_FONT_SIZE as f32,
+            0.0,
+            Color::BLACK,
+        );
+        if parent[vertex] == vertex {
+            d.draw_text_ex(
+                font,
+                &format!("r{}", uf.ranks()[vertex]),
+                *center
+                    - Vector2 {
+                        x: 0.5 * NODE_RADIUS,
+                        y: NODE_RADIUS + RANK_FONT_SIZE as f32,
+                    },
+                RANK_FONT_SIZE as f32,
+                0.0,
+                Color::DARKGRAY,
+            );
+        }
+    }
+}
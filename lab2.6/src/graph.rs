@@ -1,11 +1,6 @@
 use std::{collections::VecDeque, fmt::Display};
 
-use rand::{Rng, SeedableRng, rngs::SmallRng};
-
-// $\mathtt{\overline{n_1 n_2 n_3 n_4} = 4228}$
-pub const DEFAULT_ROWS: &[usize] = &[4, 3, 5];
-pub const VERTEX_COUNT: usize = 12; // $\mathtt{10 + n_3} = 12$
-const RANDOM_SEED: u64 = 4228;
+use crate::rng::Xoshiro256;
 
 #[derive(Clone)]
 pub struct Graph(pub Vec<Vec<Option<u32>>>);
@@ -27,33 +22,31 @@ impl Display for Graph {
 }
 
 impl Graph {
-    pub fn generate(k: f32) -> Self {
-        const ITER_LEN: usize = VERTEX_COUNT * VERTEX_COUNT;
-        let mut rng = SmallRng::seed_from_u64(RANDOM_SEED);
+    pub fn generate(size: usize, seed: u64, k: f32) -> Self {
+        let iter_len = size * size;
+        let mut rng = Xoshiro256::seed_from_u64(seed);
         let iter = std::iter::repeat_with(move || rng.random_range(0.0..2.0));
 
         let mut adj_undir: Vec<Vec<u32>> = iter
             .clone()
-            .take(ITER_LEN)
+            .take(iter_len)
             .map(|i| f32::min(i * k, 1.0) as u32)
             .collect::<Vec<_>>()
-            .chunks(VERTEX_COUNT)
+            .chunks(size)
             .map(|row| row.to_vec())
             .collect();
-        for i in 0..VERTEX_COUNT {
-            for j in (i + 1)..VERTEX_COUNT {
+        for i in 0..size {
+            for j in (i + 1)..size {
                 adj_undir[j][i] = adj_undir[i][j];
             }
         }
 
         let c: Vec<Vec<u32>> = iter
-            .take(ITER_LEN)
+            .take(iter_len)
             .enumerate()
-            .map(|(i, w)| {
-                (w * 100.0 * adj_undir[i / VERTEX_COUNT][i % VERTEX_COUNT] as f32).ceil() as u32
-            })
+            .map(|(i, w)| (w * 100.0 * adj_undir[i / size][i % size] as f32).ceil() as u32)
             .collect::<Vec<_>>()
-            .chunks(VERTEX_COUNT)
+            .chunks(size)
             .map(|row| row.to_vec())
             .collect();
 
@@ -73,9 +66,9 @@ impl Graph {
             })
             .collect();
 
-        let mut weights: Vec<Vec<Option<u32>>> = vec![vec![Some(0); VERTEX_COUNT]; VERTEX_COUNT];
-        for i in 0..VERTEX_COUNT {
-            for j in (i + 1)..VERTEX_COUNT {
+        let mut weights: Vec<Vec<Option<u32>>> = vec![vec![Some(0); size]; size];
+        for i in 0..size {
+            for j in (i + 1)..size {
                 let weight = c[i][j] * (d[i][j] + h[i][j] * (i < j) as u32);
                 weights[i][j] = if weight != 0 { Some(weight) } else { None };
                 weights[j][i] = if weight != 0 { Some(weight) } else { None };
@@ -85,34 +78,411 @@ impl Graph {
         Graph(weights)
     }
 
-    pub fn kruskal_step(edges: &mut VecDeque<(usize, usize, u32)>, step: &mut KruskalStep) -> bool {
-        if step.tree.len() == step.uf.rank.len() - 1 {
-            println!("MST built.");
-            step.current = None;
-            return false;
+    pub fn add_vertex(&mut self) -> usize {
+        let vertex = self.0.len();
+        self.0.iter_mut().for_each(|row| row.push(None));
+        self.0.push(vec![None; vertex + 1]);
+        self.0[vertex][vertex] = Some(0);
+        vertex
+    }
+
+    pub fn remove_vertex(&mut self, vertex: usize) {
+        self.0.remove(vertex);
+        self.0.iter_mut().for_each(|row| {
+            row.remove(vertex);
+        });
+    }
+
+    pub fn set_edge(&mut self, from: usize, to: usize, weight: Option<u32>) {
+        self.0[from][to] = weight;
+        self.0[to][from] = weight;
+    }
+
+    pub fn degree(&self, vertex: usize) -> usize {
+        self.0[vertex]
+            .iter()
+            .enumerate()
+            .filter(|(i, weight)| *i != vertex && weight.is_some())
+            .count()
+    }
+
+    /// Undirected edges `(i, j)` with `i < j`.
+    pub fn edges(&self) -> Vec<(usize, usize)> {
+        (0..self.0.len())
+            .flat_map(|i| ((i + 1)..self.0.len()).map(move |j| (i, j)))
+            .filter(|(i, j)| self.0[*i][*j].is_some())
+            .collect()
+    }
+
+    /// Undirected edges `(i, j, weight)` with `i < j`, lightest first.
+    pub fn sorted_edges(&self) -> VecDeque<(usize, usize, u32)> {
+        let mut edges: Vec<(usize, usize, u32)> = self
+            .0
+            .iter()
+            .enumerate()
+            .flat_map(|(i, row)| {
+                row.iter()
+                    .enumerate()
+                    .filter_map(move |(j, &cell)| cell.map(|w| (i, j, w)))
+            })
+            .filter(|(i, j, _)| *i < *j)
+            .collect();
+        edges.sort_by_key(|(_, _, w)| *w);
+        VecDeque::from(edges)
+    }
+
+    /// The same graph with vertex `i` renumbered to `permutation[i]`.
+    pub fn permuted(&self, permutation: &[usize]) -> Graph {
+        let size = self.0.len();
+        let mut taken = vec![false; size];
+        assert!(
+            permutation.len() == size
+                && permutation
+                    .iter()
+                    .all(|new| *new < size && !std::mem::replace(&mut taken[*new], true)),
+            "not a permutation of {} vertices: {:?}",
+            size,
+            permutation
+        );
+        // $\mathtt{result[p_i][p_j] = weights[i][j]}$
+        let mut result = vec![vec![None; size]; size];
+        for i in 0..size {
+            for j in 0..size {
+                result[permutation[i]][permutation[j]] = self.0[i][j];
+            }
         }
+        Graph(result)
+    }
 
-        if let Some(current) = step.current {
-            if step.uf.find(current.0) == step.uf.find(current.1) {
-                println!("{:?} would create a cycle", current);
-            } else {
-                println!("{:?} added to MST.", current);
-                step.uf.union(current.0, current.1);
-                step.tree.push(current);
+    /// Vertices joined to `vertex` by an edge, the loop left out.
+    fn neighbours(&self, vertex: usize) -> Vec<usize> {
+        (0..self.0.len())
+            .filter(|other| *other != vertex && self.0[vertex][*other].is_some())
+            .collect()
+    }
+
+    /// Renumbers the vertices from the highest degree down, ties keeping
+    /// their old order.
+    pub fn degree_order(&self) -> Vec<usize> {
+        let mut vertices: Vec<usize> = (0..self.0.len()).collect();
+        vertices.sort_by_key(|vertex| std::cmp::Reverse(self.degree(*vertex)));
+        permutation(&vertices)
+    }
+
+    /// Reverse Cuthill–McKee: a BFS of every component from its vertex of
+    /// lowest degree, neighbours taken by increasing degree, numbered
+    /// backwards. Neighbours end up with close numbers, so the weights of
+    /// the permuted matrix gather around the diagonal and `bandwidth` drops.
+    pub fn reverse_cuthill_mckee(&self) -> Vec<usize> {
+        let size = self.0.len();
+        let neighbours: Vec<Vec<usize>> = (0..size).map(|vertex| self.neighbours(vertex)).collect();
+        let mut visited = vec![false; size];
+        let mut vertices = Vec::with_capacity(size);
+        while let Some(root) = (0..size)
+            .filter(|vertex| !visited[*vertex])
+            .min_by_key(|vertex| neighbours[*vertex].len())
+        {
+            visited[root] = true;
+            let mut queue = VecDeque::from([root]);
+            while let Some(vertex) = queue.pop_front() {
+                vertices.push(vertex);
+                let mut next: Vec<usize> = neighbours[vertex]
+                    .iter()
+                    .copied()
+                    .filter(|other| !visited[*other])
+                    .collect();
+                next.sort_by_key(|other| neighbours[*other].len());
+                for other in next {
+                    visited[other] = true;
+                    queue.push_back(other);
+                }
             }
-            step.current = edges.pop_front();
-        } else if let Some(next) = edges.pop_front() {
-            step.current = Some(next);
+        }
+        vertices.reverse();
+        permutation(&vertices)
+    }
+
+    /// Largest distance between the row and column of an edge, how far the
+    /// weights stray from the diagonal.
+    pub fn bandwidth(&self) -> usize {
+        self.edges()
+            .iter()
+            .map(|(i, j)| i.abs_diff(*j))
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn components(&self) -> Vec<Vec<usize>> {
+        let mut uf = UnionFind::new(self.0.len());
+        self.sorted_edges().iter().for_each(|(i, j, _)| {
+            uf.union(*i, *j);
+        });
+        uf.sets()
+    }
+
+    /// Whether every vertex can reach every other one; the empty graph is.
+    pub fn is_connected(&self) -> bool {
+        self.components().len() <= 1
+    }
+
+    /// Whole spanning tree at once, without the step-by-step output. On a
+    /// disconnected graph this is a minimum spanning forest, a tree for each
+    /// component.
+    pub fn minimum_spanning_tree(&self) -> Vec<(usize, usize, u32)> {
+        let mut uf = UnionFind::new(self.0.len());
+        self.sorted_edges()
+            .into_iter()
+            .filter(|(i, j, _)| uf.union(*i, *j))
+            .collect()
+    }
+
+    /// Heaviest edge on the path `tree` takes between `from` and `to`.
+    fn heaviest_on_path(
+        &self,
+        tree: &[(usize, usize, u32)],
+        from: usize,
+        to: usize,
+    ) -> Option<(usize, usize, u32)> {
+        path(self.0.len(), tree.iter(), from, to)?
+            .windows(2)
+            .map(|pair| {
+                let (i, j) = (pair[0].min(pair[1]), pair[0].max(pair[1]));
+                (i, j, self.0[i][j].unwrap())
+            })
+            .max_by_key(|(_, _, w)| *w)
+    }
+
+    /// How far the weight of the edge between `from` and `to` can move
+    /// before `minimum_spanning_tree` stops being a minimum one, `None` if
+    /// there is no such edge. Loops never are in a spanning tree.
+    pub fn slack(&self, from: usize, to: usize) -> Option<Slack> {
+        let (from, to) = (from.min(to), from.max(to));
+        if from == to {
+            return None;
+        }
+        let weight = self.0[from][to]?;
+        let tree = self.minimum_spanning_tree();
+        if tree.iter().any(is_edge(from, to)) {
+            // the cheapest edge whose tree path runs through this one
+            let replacement = self
+                .sorted_edges()
+                .into_iter()
+                .filter(|edge| !tree.contains(edge))
+                .find(|(i, j, _)| {
+                    path(self.0.len(), tree.iter(), *i, *j).is_some_and(|cycle| {
+                        cycle
+                            .windows(2)
+                            .any(|pair| pair == [from, to] || pair == [to, from])
+                    })
+                });
+            Some(Slack::Tree {
+                increase: replacement.map(|(_, _, w)| w - weight),
+                replacement,
+            })
         } else {
-            return false;
+            let heaviest = self
+                .heaviest_on_path(&tree, from, to)
+                .expect("the tree joins the ends of every edge");
+            Some(Slack::NonTree {
+                decrease: weight - heaviest.2,
+                heaviest,
+            })
         }
-        true
     }
+
+    /// Whether no other spanning tree weighs as little: every edge outside
+    /// the tree is heavier than each one on the tree path between its ends.
+    pub fn has_unique_mst(&self) -> bool {
+        let tree = self.minimum_spanning_tree();
+        self.sorted_edges()
+            .iter()
+            .filter(|edge| !tree.contains(edge))
+            .all(|(i, j, w)| {
+                self.heaviest_on_path(&tree, *i, *j)
+                    .is_none_or(|h| h.2 < *w)
+            })
+    }
+
+    /// Lightest spanning tree other than `minimum_spanning_tree`, which
+    /// differs from it in one edge. Weighs as much as the minimum one unless
+    /// that is unique, `None` if the graph has no other spanning tree.
+    pub fn second_best_spanning_tree(&self) -> Option<SecondBest> {
+        let tree = self.minimum_spanning_tree();
+        let (removed, added) = self
+            .sorted_edges()
+            .into_iter()
+            .filter(|edge| !tree.contains(edge))
+            .filter_map(|(i, j, w)| {
+                let heaviest = self.heaviest_on_path(&tree, i, j)?;
+                Some((heaviest, (i, j, w)))
+            })
+            .min_by_key(|(heaviest, added)| added.2 - heaviest.2)?;
+        let mut edges: Vec<(usize, usize, u32)> =
+            tree.into_iter().filter(|edge| *edge != removed).collect();
+        edges.push(added);
+        Some(SecondBest {
+            weight: edges.iter().map(|(_, _, w)| *w as usize).sum(),
+            edges,
+            removed,
+            added,
+        })
+    }
+
+    /// Dijkstra from `root`: the distance to every vertex and the one before
+    /// it on a shortest path, `None` where `root` cannot reach.
+    pub fn shortest_paths(&self, root: usize) -> (Vec<Option<u32>>, Vec<Option<usize>>) {
+        let size = self.0.len();
+        let mut distance = vec![None; size];
+        let mut previous = vec![None; size];
+        let mut done = vec![false; size];
+        distance[root] = Some(0);
+        while let Some(closest) = (0..size)
+            .filter(|vertex| !done[*vertex] && distance[*vertex].is_some())
+            .min_by_key(|vertex| distance[*vertex])
+        {
+            done[closest] = true;
+            let reached = distance[closest].unwrap();
+            for next in 0..size {
+                if let Some(weight) = self.0[closest][next]
+                    && !done[next]
+                    && distance[next].is_none_or(|old| reached + weight < old)
+                {
+                    distance[next] = Some(reached + weight);
+                    previous[next] = Some(closest);
+                }
+            }
+        }
+        (distance, previous)
+    }
+
+    /// Every vertex `root` reaches joined to the one before it on its
+    /// shortest path.
+    pub fn shortest_path_tree(&self, root: usize) -> Vec<(usize, usize, u32)> {
+        let (_, previous) = self.shortest_paths(root);
+        previous
+            .iter()
+            .enumerate()
+            .filter_map(|(vertex, previous)| {
+                let previous = (*previous)?;
+                let (i, j) = (vertex.min(previous), vertex.max(previous));
+                Some((i, j, self.0[i][j].unwrap()))
+            })
+            .collect()
+    }
+
+    /// Tree joining all of `terminals` through whichever other vertices
+    /// help, at most twice as heavy as the lightest one: the MST of their
+    /// metric closure with every closure edge expanded into its shortest
+    /// path, the MST of what that covers, then non-terminal leaves pruned.
+    /// Terminals in different components get a tree each.
+    pub fn steiner_tree(&self, terminals: &[usize]) -> Vec<(usize, usize, u32)> {
+        let paths: Vec<_> = terminals
+            .iter()
+            .map(|terminal| self.shortest_paths(*terminal))
+            .collect();
+        let mut closure: Vec<(usize, usize, u32)> = (0..terminals.len())
+            .flat_map(|a| ((a + 1)..terminals.len()).map(move |b| (a, b)))
+            .filter_map(|(a, b)| Some((a, b, paths[a].0[terminals[b]]?)))
+            .collect();
+        closure.sort_by_key(|(_, _, distance)| *distance);
+
+        let mut closure_uf = UnionFind::new(terminals.len());
+        let mut covered: Vec<(usize, usize, u32)> = Vec::new();
+        for (a, b, _) in closure {
+            if !closure_uf.union(a, b) {
+                continue;
+            }
+            let previous = &paths[a].1;
+            let mut vertex = terminals[b];
+            while let Some(before) = previous[vertex] {
+                let (i, j) = (vertex.min(before), vertex.max(before));
+                covered.push((i, j, self.0[i][j].unwrap()));
+                vertex = before;
+            }
+        }
+        covered.sort_by_key(|(i, j, w)| (*w, *i, *j));
+        covered.dedup();
+
+        let mut uf = UnionFind::new(self.0.len());
+        let mut tree: Vec<(usize, usize, u32)> = covered
+            .into_iter()
+            .filter(|(i, j, _)| uf.union(*i, *j))
+            .collect();
+        while let Some(leaf) = tree.iter().position(|(i, j, _)| {
+            [*i, *j].iter().any(|end| {
+                !terminals.contains(end)
+                    && tree.iter().filter(|(i, j, _)| i == end || j == end).count() == 1
+            })
+        }) {
+            tree.remove(leaf);
+        }
+        tree
+    }
+}
+
+/// Permutation that gives `vertices[i]` the new number `i`, from a list of
+/// every vertex in its new order.
+pub fn permutation(vertices: &[usize]) -> Vec<usize> {
+    let mut result = vec![usize::MAX; vertices.len()];
+    for (new, old) in vertices.iter().enumerate() {
+        result[*old] = new;
+    }
+    assert!(
+        result.iter().all(|new| *new != usize::MAX),
+        "not a vertex order: {:?}",
+        vertices
+    );
+    result
+}
+
+/// The runner-up spanning tree: the minimum one with `removed` swapped for
+/// `added`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SecondBest {
+    pub edges: Vec<(usize, usize, u32)>,
+    pub weight: usize,
+    pub removed: (usize, usize, u32),
+    pub added: (usize, usize, u32),
 }
 
+/// How far an edge's weight can move before the minimum spanning tree
+/// changes. At exactly that far another tree weighs as much.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Slack {
+    /// In the tree, which stays minimum while the edge gets up to
+    /// `increase` heavier; past that `replacement` takes its place. Bridges
+    /// have neither, nothing can replace them.
+    Tree {
+        increase: Option<u32>,
+        replacement: Option<(usize, usize, u32)>,
+    },
+    /// Out of the tree until it gets more than `decrease` lighter; past that
+    /// it replaces `heaviest`, the heaviest edge on the tree path between its
+    /// ends.
+    NonTree {
+        decrease: u32,
+        heaviest: (usize, usize, u32),
+    },
+}
+
+/// A parent pointer `find` or `union` moved: `vertex` pointed at `from`
+/// and now points at `to`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Relink {
+    pub vertex: usize,
+    pub from: usize,
+    pub to: usize,
+    /// Moved by `union`, hanging a root under another one, rather than by
+    /// path compression in `find`.
+    pub union: bool,
+}
+
+#[derive(Clone, PartialEq)]
 pub struct UnionFind {
     parent: Vec<usize>,
     rank: Vec<usize>,
+    /// Pointers moved since `clear_changes`, oldest first.
+    changes: Vec<Relink>,
 }
 
 impl UnionFind {
@@ -120,12 +490,65 @@ impl UnionFind {
         UnionFind {
             parent: (0..n).collect(),
             rank: vec![0; n],
+            changes: Vec::new(),
+        }
+    }
+
+    /// Parent of every vertex, roots point at themselves.
+    pub fn parents(&self) -> &[usize] {
+        &self.parent
+    }
+
+    /// Upper bound on the height of the tree under each root.
+    pub fn ranks(&self) -> &[usize] {
+        &self.rank
+    }
+
+    pub fn changes(&self) -> &[Relink] {
+        &self.changes
+    }
+
+    pub fn clear_changes(&mut self) {
+        self.changes.clear();
+    }
+
+    /// Same as `find`, but leaves the path as it is.
+    pub fn root(&self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            x = self.parent[x];
         }
+        x
+    }
+
+    /// Vertices of every set in increasing order, sets by their smallest
+    /// vertex. Follows the parents without compressing them.
+    pub fn sets(&self) -> Vec<Vec<usize>> {
+        let mut result: Vec<Vec<usize>> = Vec::new();
+        let mut roots: Vec<usize> = Vec::new();
+        for vertex in 0..self.parent.len() {
+            let root = self.root(vertex);
+            if let Some(index) = roots.iter().position(|r| *r == root) {
+                result[index].push(vertex);
+            } else {
+                roots.push(root);
+                result.push(vec![vertex]);
+            }
+        }
+        result
     }
 
     pub fn find(&mut self, x: usize) -> usize {
         if self.parent[x] != x {
-            self.parent[x] = self.find(self.parent[x]);
+            let root = self.find(self.parent[x]);
+            if root != self.parent[x] {
+                self.changes.push(Relink {
+                    vertex: x,
+                    from: self.parent[x],
+                    to: root,
+                    union: false,
+                });
+                self.parent[x] = root;
+            }
         }
         self.parent[x]
     }
@@ -138,39 +561,878 @@ impl UnionFind {
             return false;
         }
 
-        match self.rank[x_root].cmp(&self.rank[y_root]) {
-            std::cmp::Ordering::Less => {
-                self.parent[x_root] = y_root;
-            }
-            std::cmp::Ordering::Greater => {
-                self.parent[y_root] = x_root;
-            }
+        let (child, root) = match self.rank[x_root].cmp(&self.rank[y_root]) {
+            std::cmp::Ordering::Less => (x_root, y_root),
+            std::cmp::Ordering::Greater => (y_root, x_root),
             std::cmp::Ordering::Equal => {
-                self.parent[y_root] = x_root;
                 self.rank[x_root] += 1;
+                (y_root, x_root)
             }
-        }
+        };
+        self.parent[child] = root;
+        self.changes.push(Relink {
+            vertex: child,
+            from: child,
+            to: root,
+            union: true,
+        });
 
         true
     }
 }
 
+/// Vertices on a path from `from` to `to` using only `edges`, fewest edges
+/// first, if there is one.
+fn path<'a>(
+    vertex_count: usize,
+    edges: impl Iterator<Item = &'a (usize, usize, u32)> + Clone,
+    from: usize,
+    to: usize,
+) -> Option<Vec<usize>> {
+    let mut parent = vec![None; vertex_count];
+    parent[from] = Some(from);
+    let mut queue = VecDeque::from([from]);
+    while let Some(vertex) = queue.pop_front() {
+        if vertex == to {
+            let mut path = vec![to];
+            while let Some(previous) = parent[*path.last().unwrap()]
+                && previous != *path.last().unwrap()
+            {
+                path.push(previous);
+            }
+            path.reverse();
+            return Some(path);
+        }
+        for (i, j, _) in edges.clone() {
+            let next = if *i == vertex {
+                *j
+            } else if *j == vertex {
+                *i
+            } else {
+                continue;
+            };
+            if parent[next].is_none() {
+                parent[next] = Some(vertex);
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+fn format_edges(edges: &[(usize, usize, u32)]) -> String {
+    edges
+        .iter()
+        .map(|(i, j, w)| format!("{} -- {} ({})", i + 1, j + 1, w))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn format_cycle(cycle: &[usize]) -> String {
+    cycle
+        .iter()
+        .chain(cycle.first())
+        .map(|vertex| (vertex + 1).to_string())
+        .collect::<Vec<_>>()
+        .join(" -- ")
+}
+
+/// What one step of a spanning tree algorithm did.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    /// Took the next edge, to be decided on next step.
+    Considered((usize, usize, u32)),
+    Accepted((usize, usize, u32)),
+    /// Left out since the tree already joins its ends, along `cycle`: the
+    /// vertices from one end to the other.
+    Rejected {
+        edge: (usize, usize, u32),
+        cycle: Vec<usize>,
+    },
+    /// Borůvka: the cheapest edge out of every component, added next step.
+    Cheapest {
+        round: usize,
+        edges: Vec<(usize, usize, u32)>,
+    },
+    /// Borůvka: the cheapest edges of a round joined their components.
+    Merged(Vec<(usize, usize, u32)>),
+    /// Reverse-delete: taken out, its ends stay joined along `cycle`.
+    Deleted {
+        edge: (usize, usize, u32),
+        cycle: Vec<usize>,
+    },
+    /// Reverse-delete: kept, taking it out would split a component.
+    Kept((usize, usize, u32)),
+    /// Nothing left to do; the graph is disconnected if this built more
+    /// than one tree.
+    Finished {
+        trees: usize,
+    },
+}
+
+impl Display for Slack {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Slack::Tree {
+                increase: Some(increase),
+                replacement: Some((i, j, w)),
+            } => write!(
+                f,
+                "+{}, then {} -- {} ({}) replaces it",
+                increase,
+                i + 1,
+                j + 1,
+                w
+            ),
+            Slack::Tree { .. } => write!(f, "any, it is a bridge"),
+            Slack::NonTree {
+                decrease,
+                heaviest: (i, j, w),
+            } => write!(
+                f,
+                "-{}, then it replaces {} -- {} ({})",
+                decrease,
+                i + 1,
+                j + 1,
+                w
+            ),
+        }
+    }
+}
+
+impl Display for SecondBest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (removed, added) = (self.removed, self.added);
+        write!(
+            f,
+            "weight {}, {} swapped for {}\n{}",
+            self.weight,
+            format_edges(&[removed]),
+            format_edges(&[added]),
+            format_edges(&self.edges)
+        )
+    }
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outcome::Considered((i, j, w)) => {
+                write!(f, "Considering {} -- {} ({})", i + 1, j + 1, w)
+            }
+            Outcome::Accepted((i, j, w)) => {
+                write!(f, "{} -- {} ({}) added to MST", i + 1, j + 1, w)
+            }
+            Outcome::Rejected {
+                edge: (i, j, w),
+                cycle,
+            } => write!(
+                f,
+                "{} -- {} ({}) would close the cycle {}",
+                i + 1,
+                j + 1,
+                w,
+                format_cycle(cycle)
+            ),
+            Outcome::Cheapest { round, edges } => {
+                write!(f, "Round {}: cheapest {}", round, format_edges(edges))
+            }
+            Outcome::Merged(edges) => write!(f, "{} added to MST", format_edges(edges)),
+            Outcome::Deleted {
+                edge: (i, j, w),
+                cycle,
+            } => write!(
+                f,
+                "{} -- {} ({}) deleted, the cycle {} stays",
+                i + 1,
+                j + 1,
+                w,
+                format_cycle(cycle)
+            ),
+            Outcome::Kept((i, j, w)) => {
+                write!(f, "{} -- {} ({}) kept, it is a bridge", i + 1, j + 1, w)
+            }
+            Outcome::Finished { trees: 0 } => write!(f, "Empty graph, nothing to span"),
+            Outcome::Finished { trees: 1 } => write!(f, "MST built"),
+            Outcome::Finished { trees } => write!(
+                f,
+                "Graph is disconnected, spanning forest of {} trees built",
+                trees
+            ),
+        }
+    }
+}
+
+/// Where an edge stands in a spanning tree algorithm, which all three draw
+/// the same way.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EdgeState {
+    /// Not looked at yet.
+    Pending,
+    Considering,
+    Tree,
+    /// Looked at and left out for good.
+    Discarded,
+}
+
+impl EdgeState {
+    pub fn name(&self) -> &'static str {
+        match self {
+            EdgeState::Pending => "pending",
+            EdgeState::Considering => "considering",
+            EdgeState::Tree => "in tree",
+            EdgeState::Discarded => "discarded",
+        }
+    }
+}
+
+/// One tree of a minimum spanning forest and the component it spans.
+pub struct SpanningTree {
+    pub vertices: Vec<usize>,
+    pub edges: Vec<(usize, usize, u32)>,
+    pub weight: usize,
+}
+
+/// A minimum spanning tree algorithm taken one step at a time, so the
+/// window can show any of them the same way.
+pub trait Spanning {
+    fn name(&self) -> &'static str;
+    fn vertex_count(&self) -> usize;
+    /// Takes one step and adds its outcome to `history`; `None` once there
+    /// is nothing left to do.
+    fn step(&mut self) -> Option<Outcome>;
+    /// Every step taken so far, oldest first.
+    fn history(&self) -> &[Outcome];
+    /// Edges known to be in the tree so far.
+    fn tree(&self) -> &[(usize, usize, u32)];
+    /// State of the edge `(from, to)`, `from < to`.
+    fn edge_state(&self, from: usize, to: usize) -> EdgeState;
+    /// Disjoint sets the algorithm keeps, with the pointers the last step
+    /// moved, if it keeps any.
+    fn union_find(&self) -> Option<&UnionFind>;
+
+    fn weight_sum(&self) -> usize {
+        self.tree().iter().map(|(_, _, w)| *w as usize).sum()
+    }
+
+    /// The tree built so far split by the vertices it joins, a tree for
+    /// each component once it is finished.
+    fn forest(&self) -> Vec<SpanningTree> {
+        let mut uf = UnionFind::new(self.vertex_count());
+        for (i, j, _) in self.tree() {
+            uf.union(*i, *j);
+        }
+        uf.sets()
+            .into_iter()
+            .map(|vertices| {
+                let edges: Vec<(usize, usize, u32)> = self
+                    .tree()
+                    .iter()
+                    .filter(|(i, _, _)| vertices.contains(i))
+                    .copied()
+                    .collect();
+                SpanningTree {
+                    weight: edges.iter().map(|(_, _, w)| *w as usize).sum(),
+                    vertices,
+                    edges,
+                }
+            })
+            .collect()
+    }
+}
+
+fn is_edge(from: usize, to: usize) -> impl Fn(&(usize, usize, u32)) -> bool {
+    move |(i, j, _)| *i == from && *j == to
+}
+
+/// Kruskal's algorithm: edges lightest first, each one added unless the
+/// tree already joins its ends.
+#[derive(Clone, PartialEq)]
 pub struct KruskalStep {
     pub current: Option<(usize, usize, u32)>,
     pub tree: Vec<(usize, usize, u32)>,
+    /// Edges not looked at yet, lightest first.
+    pub remaining: VecDeque<(usize, usize, u32)>,
+    pub history: Vec<Outcome>,
     uf: UnionFind,
 }
 
 impl KruskalStep {
-    pub fn new(vertex_count: usize) -> Self {
+    pub fn new(graph: &Graph) -> Self {
         KruskalStep {
             current: None,
-            tree: Vec::with_capacity(vertex_count - 1),
-            uf: UnionFind::new(vertex_count),
+            tree: Vec::with_capacity(graph.0.len().saturating_sub(1)),
+            remaining: graph.sorted_edges(),
+            history: Vec::new(),
+            uf: UnionFind::new(graph.0.len()),
+        }
+    }
+}
+
+impl Spanning for KruskalStep {
+    fn name(&self) -> &'static str {
+        "Kruskal"
+    }
+
+    fn vertex_count(&self) -> usize {
+        self.uf.parent.len()
+    }
+
+    /// Takes the lightest edge left, or decides on the one taken last time.
+    fn step(&mut self) -> Option<Outcome> {
+        if let Some(Outcome::Finished { .. }) = self.history.last() {
+            return None;
+        }
+        self.uf.clear_changes();
+
+        let outcome = if let Some(current) = self.current.take() {
+            if self.uf.find(current.0) == self.uf.find(current.1) {
+                let cycle = path(self.vertex_count(), self.tree.iter(), current.0, current.1)
+                    .expect("ends in one set are joined by the tree");
+                Outcome::Rejected {
+                    edge: current,
+                    cycle,
+                }
+            } else {
+                self.uf.union(current.0, current.1);
+                self.tree.push(current);
+                Outcome::Accepted(current)
+            }
+        } else if self.tree.len() + 1 == self.vertex_count() {
+            Outcome::Finished { trees: 1 }
+        } else if let Some(next) = self.remaining.pop_front() {
+            self.current = Some(next);
+            Outcome::Considered(next)
+        } else {
+            // out of edges before the tree spans everything: one tree per
+            // component, none for no vertices
+            Outcome::Finished {
+                trees: self.vertex_count() - self.tree.len(),
+            }
+        };
+        self.history.push(outcome.clone());
+        Some(outcome)
+    }
+
+    fn history(&self) -> &[Outcome] {
+        &self.history
+    }
+
+    fn tree(&self) -> &[(usize, usize, u32)] {
+        &self.tree
+    }
+
+    fn edge_state(&self, from: usize, to: usize) -> EdgeState {
+        if self.current.iter().any(is_edge(from, to)) {
+            EdgeState::Considering
+        } else if self.tree.iter().any(is_edge(from, to)) {
+            EdgeState::Tree
+        } else if self.remaining.iter().any(is_edge(from, to)) {
+            EdgeState::Pending
+        } else {
+            EdgeState::Discarded
+        }
+    }
+
+    fn union_find(&self) -> Option<&UnionFind> {
+        Some(&self.uf)
+    }
+}
+
+/// Borůvka's algorithm: in every round each component picks the cheapest
+/// edge out of it, then all of them are added at once. Ties go to the edge
+/// listed first, so the picks never close a cycle.
+#[derive(Clone, PartialEq)]
+pub struct BoruvkaStep {
+    pub tree: Vec<(usize, usize, u32)>,
+    /// Edges picked this round, to be added next step.
+    pub cheapest: Vec<(usize, usize, u32)>,
+    pub round: usize,
+    pub history: Vec<Outcome>,
+    /// Every edge, lightest first.
+    edges: Vec<(usize, usize, u32)>,
+    uf: UnionFind,
+}
+
+impl BoruvkaStep {
+    pub fn new(graph: &Graph) -> Self {
+        BoruvkaStep {
+            tree: Vec::with_capacity(graph.0.len().saturating_sub(1)),
+            cheapest: Vec::new(),
+            round: 0,
+            history: Vec::new(),
+            edges: graph.sorted_edges().into(),
+            uf: UnionFind::new(graph.0.len()),
+        }
+    }
+}
+
+impl Spanning for BoruvkaStep {
+    fn name(&self) -> &'static str {
+        "Borůvka"
+    }
+
+    fn vertex_count(&self) -> usize {
+        self.uf.parent.len()
+    }
+
+    /// Picks the cheapest edge out of every component, or adds the ones
+    /// picked last time.
+    fn step(&mut self) -> Option<Outcome> {
+        if let Some(Outcome::Finished { .. }) = self.history.last() {
+            return None;
+        }
+        self.uf.clear_changes();
+
+        let outcome = if !self.cheapest.is_empty() {
+            let added: Vec<(usize, usize, u32)> = std::mem::take(&mut self.cheapest)
+                .into_iter()
+                .filter(|(i, j, _)| self.uf.union(*i, *j))
+                .collect();
+            self.tree.extend(&added);
+            Outcome::Merged(added)
+        } else {
+            // cheapest edge out of each component, by its root
+            let mut best: Vec<Option<usize>> = vec![None; self.vertex_count()];
+            for (index, (i, j, _)) in self.edges.iter().enumerate() {
+                let (a, b) = (self.uf.find(*i), self.uf.find(*j));
+                if a != b {
+                    best[a].get_or_insert(index);
+                    best[b].get_or_insert(index);
+                }
+            }
+            let mut picked: Vec<usize> = best.into_iter().flatten().collect();
+            picked.sort();
+            picked.dedup();
+            if picked.is_empty() {
+                Outcome::Finished {
+                    trees: self.vertex_count() - self.tree.len(),
+                }
+            } else {
+                self.round += 1;
+                self.cheapest = picked.into_iter().map(|index| self.edges[index]).collect();
+                Outcome::Cheapest {
+                    round: self.round,
+                    edges: self.cheapest.clone(),
+                }
+            }
+        };
+        self.history.push(outcome.clone());
+        Some(outcome)
+    }
+
+    fn history(&self) -> &[Outcome] {
+        &self.history
+    }
+
+    fn tree(&self) -> &[(usize, usize, u32)] {
+        &self.tree
+    }
+
+    fn edge_state(&self, from: usize, to: usize) -> EdgeState {
+        if self.cheapest.iter().any(is_edge(from, to)) {
+            EdgeState::Considering
+        } else if self.tree.iter().any(is_edge(from, to)) {
+            EdgeState::Tree
+        } else if self.uf.root(from) == self.uf.root(to) {
+            EdgeState::Discarded
+        } else {
+            EdgeState::Pending
+        }
+    }
+
+    fn union_find(&self) -> Option<&UnionFind> {
+        Some(&self.uf)
+    }
+}
+
+/// Reverse-delete: edges heaviest first, each one deleted unless that
+/// splits its component. What is left in the end is the tree.
+#[derive(Clone, PartialEq)]
+pub struct ReverseDeleteStep {
+    pub current: Option<(usize, usize, u32)>,
+    pub kept: Vec<(usize, usize, u32)>,
+    pub deleted: Vec<(usize, usize, u32)>,
+    /// Edges not looked at yet, heaviest first.
+    pub remaining: VecDeque<(usize, usize, u32)>,
+    pub history: Vec<Outcome>,
+    vertex_count: usize,
+}
+
+impl ReverseDeleteStep {
+    pub fn new(graph: &Graph) -> Self {
+        ReverseDeleteStep {
+            current: None,
+            kept: Vec::new(),
+            deleted: Vec::new(),
+            remaining: graph.sorted_edges().into_iter().rev().collect(),
+            history: Vec::new(),
+            vertex_count: graph.0.len(),
+        }
+    }
+}
+
+impl Spanning for ReverseDeleteStep {
+    fn name(&self) -> &'static str {
+        "Reverse-delete"
+    }
+
+    fn vertex_count(&self) -> usize {
+        self.vertex_count
+    }
+
+    /// Takes the heaviest edge left, or decides on the one taken last time.
+    fn step(&mut self) -> Option<Outcome> {
+        if let Some(Outcome::Finished { .. }) = self.history.last() {
+            return None;
+        }
+
+        let outcome = if let Some(current) = self.current.take() {
+            // the graph without `current` and what is already deleted
+            let others = self.kept.iter().chain(&self.remaining);
+            match path(self.vertex_count, others, current.0, current.1) {
+                Some(cycle) => {
+                    self.deleted.push(current);
+                    Outcome::Deleted {
+                        edge: current,
+                        cycle,
+                    }
+                }
+                None => {
+                    self.kept.push(current);
+                    Outcome::Kept(current)
+                }
+            }
+        } else if let Some(next) = self.remaining.pop_front() {
+            self.current = Some(next);
+            Outcome::Considered(next)
+        } else {
+            Outcome::Finished {
+                trees: self.vertex_count - self.kept.len(),
+            }
+        };
+        self.history.push(outcome.clone());
+        Some(outcome)
+    }
+
+    fn history(&self) -> &[Outcome] {
+        &self.history
+    }
+
+    fn tree(&self) -> &[(usize, usize, u32)] {
+        &self.kept
+    }
+
+    fn edge_state(&self, from: usize, to: usize) -> EdgeState {
+        if self.current.iter().any(is_edge(from, to)) {
+            EdgeState::Considering
+        } else if self.kept.iter().any(is_edge(from, to)) {
+            EdgeState::Tree
+        } else if self.remaining.iter().any(is_edge(from, to)) {
+            EdgeState::Pending
+        } else {
+            EdgeState::Discarded
+        }
+    }
+
+    fn union_find(&self) -> Option<&UnionFind> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variant::{Lab, Variant};
+
+    type Edge = (usize, usize, u32);
+
+    /// Weights off the diagonal, 0 where there is no edge.
+    fn graph(rows: &[[u32; 12]]) -> Graph {
+        Graph(
+            rows.iter()
+                .enumerate()
+                .map(|(i, row)| {
+                    row.iter()
+                        .enumerate()
+                        .map(|(j, w)| (i == j || *w != 0).then_some(*w))
+                        .collect()
+                })
+                .collect(),
+        )
+    }
+
+    /// Small graphs with weights from 1 to 4, so equal weights are common.
+    fn small_graphs() -> impl Iterator<Item = Graph> {
+        (0..200).map(|seed| {
+            let mut graph = Graph::generate(1 + seed as usize % 6, seed, 1.0);
+            for row in graph.0.iter_mut() {
+                for weight in row.iter_mut().filter(|weight| **weight != Some(0)) {
+                    *weight = weight.map(|weight| 1 + weight % 4);
+                }
+            }
+            graph
+        })
+    }
+
+    /// Every spanning forest with its weight, by trying every edge subset.
+    fn spanning_forests(graph: &Graph) -> Vec<(u32, Vec<Edge>)> {
+        let edges: Vec<Edge> = graph.sorted_edges().into();
+        let size = graph.0.len() - graph.components().len();
+        (0..1u32 << edges.len())
+            .filter(|subset| subset.count_ones() as usize == size)
+            .filter_map(|subset| {
+                let mut uf = UnionFind::new(graph.0.len());
+                let mut forest: Vec<Edge> = (0..edges.len())
+                    .filter(|index| subset >> index & 1 == 1)
+                    .map(|index| edges[index])
+                    .collect();
+                if !forest.iter().all(|(i, j, _)| uf.union(*i, *j)) {
+                    return None;
+                }
+                forest.sort();
+                Some((forest.iter().map(|(_, _, w)| w).sum(), forest))
+            })
+            .collect()
+    }
+
+    fn lightest(graph: &Graph) -> u32 {
+        spanning_forests(graph)
+            .iter()
+            .map(|(w, _)| *w)
+            .min()
+            .unwrap()
+    }
+
+    fn with_weight(graph: &Graph, (i, j, _): Edge, weight: u32) -> Graph {
+        let mut graph = graph.clone();
+        graph.set_edge(i, j, Some(weight));
+        graph
+    }
+
+    /// Square, symmetric, and 0 on the diagonal.
+    fn assert_well_formed(graph: &Graph) {
+        let size = graph.0.len();
+        for i in 0..size {
+            assert_eq!(graph.0[i].len(), size);
+            assert_eq!(graph.0[i][i], Some(0));
+            for j in 0..size {
+                assert_eq!(graph.0[i][j], graph.0[j][i]);
+            }
+        }
+    }
+
+    // what the editor does to the graph
+    #[test]
+    fn edits_keep_the_matrix_square_and_symmetric() {
+        let mut graph = Graph(Vec::new());
+        for _ in 0..4 {
+            graph.add_vertex();
+            assert_well_formed(&graph);
+        }
+        assert_eq!(graph.edges(), []);
+        graph.set_edge(3, 1, Some(7));
+        graph.set_edge(0, 2, Some(5));
+        graph.set_edge(2, 3, Some(9));
+        assert_well_formed(&graph);
+        assert_eq!(graph.edges(), [(0, 2), (1, 3), (2, 3)]);
+        graph.set_edge(0, 2, None);
+        assert_well_formed(&graph);
+        assert_eq!(graph.edges(), [(1, 3), (2, 3)]);
+        graph.remove_vertex(2);
+        assert_well_formed(&graph);
+        assert_eq!(graph.sorted_edges(), [(1, 2, 7)]);
+
+        let mut generated = Graph::generate(10, 4228, 1.0);
+        let new = generated.add_vertex();
+        generated.set_edge(new, 4, Some(3));
+        generated.remove_vertex(0);
+        assert_well_formed(&generated);
+        assert_eq!(generated.0[new - 1][3], Some(3));
+    }
+
+    #[test]
+    fn slack_matches_brute_force() {
+        for graph in small_graphs() {
+            let weight = lightest(&graph);
+            for edge in graph.sorted_edges() {
+                match graph.slack(edge.1, edge.0).unwrap() {
+                    Slack::Tree {
+                        increase: Some(increase),
+                        ..
+                    } => {
+                        let heavier = edge.2 + increase;
+                        assert_eq!(
+                            lightest(&with_weight(&graph, edge, heavier)),
+                            weight + increase
+                        );
+                        assert!(
+                            lightest(&with_weight(&graph, edge, heavier + 1))
+                                < weight + increase + 1
+                        );
+                    }
+                    // a bridge is in every spanning tree
+                    Slack::Tree { .. } => {
+                        assert_eq!(
+                            lightest(&with_weight(&graph, edge, edge.2 + 100)),
+                            weight + 100
+                        )
+                    }
+                    Slack::NonTree { decrease, heaviest } => {
+                        let lighter = edge.2 - decrease;
+                        assert_eq!(lightest(&with_weight(&graph, edge, lighter)), weight);
+                        if heaviest.2 > 0 {
+                            assert!(lightest(&with_weight(&graph, edge, lighter - 1)) < weight);
+                        }
+                    }
+                }
+            }
+            assert_eq!(graph.slack(0, 0), None);
+        }
+    }
+
+    #[test]
+    fn unique_and_second_best_match_brute_force() {
+        for graph in small_graphs() {
+            let forests = spanning_forests(&graph);
+            let weight = lightest(&graph);
+            let mut tree = graph.minimum_spanning_tree();
+            tree.sort();
+            assert_eq!(
+                graph.has_unique_mst(),
+                forests.iter().filter(|(w, _)| *w == weight).count() == 1
+            );
+            let second = forests
+                .iter()
+                .filter(|(_, forest)| *forest != tree)
+                .map(|(w, _)| *w as usize)
+                .min();
+            let found = graph.second_best_spanning_tree();
+            assert_eq!(found.as_ref().map(|second| second.weight), second);
+            if let Some(mut found) = found {
+                found.edges.sort();
+                assert!(forests.iter().any(|(_, forest)| *forest == found.edges));
+            }
+        }
+    }
+
+    /// Runs `step` to the end and checks it agrees with
+    /// `minimum_spanning_tree` on `graph`.
+    fn check_steps(graph: &Graph, mut step: impl Spanning) {
+        while step.step().is_some() {}
+        let weight: u32 = graph
+            .minimum_spanning_tree()
+            .iter()
+            .map(|(_, _, w)| w)
+            .sum();
+        let trees = graph.components().len();
+        assert_eq!(step.weight_sum(), weight as usize, "{}", step.name());
+        assert_eq!(step.forest().len(), trees, "{}", step.name());
+        assert!(
+            matches!(step.history().last(), Some(Outcome::Finished { trees: t }) if *t == trees),
+            "{}",
+            step.name()
+        );
+    }
+
+    #[test]
+    fn steppers_build_the_mst() {
+        let mut isolated = Graph::generate(8, 3, 1.0);
+        for vertex in 0..8 {
+            isolated.set_edge(2, vertex, None);
+        }
+        isolated.0[2][2] = Some(0);
+        let mut split = Graph::generate(3, 5, 1.2);
+        split.add_vertex();
+        split.add_vertex();
+        split.set_edge(3, 4, Some(7));
+        let graphs = [
+            Graph(Vec::new()),
+            Graph(vec![vec![Some(0)]]),
+            isolated,
+            split,
+        ];
+        for graph in graphs.into_iter().chain(small_graphs()) {
+            check_steps(&graph, KruskalStep::new(&graph));
+            check_steps(&graph, BoruvkaStep::new(&graph));
+            check_steps(&graph, ReverseDeleteStep::new(&graph));
+        }
+    }
+
+    #[test]
+    fn steiner_tree_joins_the_terminals() {
+        for (seed, graph) in small_graphs().enumerate() {
+            let terminals: Vec<usize> = (0..graph.0.len())
+                .filter(|vertex| (seed >> vertex) & 1 == 1)
+                .collect();
+            let tree = graph.steiner_tree(&terminals);
+            let mut uf = UnionFind::new(graph.0.len());
+            assert!(tree.iter().all(|(i, j, _)| uf.union(*i, *j)));
+            assert!(tree.iter().all(|(i, j, w)| graph.0[*i][*j] == Some(*w)));
+            for a in &terminals {
+                let reachable = graph.shortest_paths(*a).0;
+                for b in &terminals {
+                    assert_eq!(uf.find(*a) == uf.find(*b), reachable[*b].is_some());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn renumbering_keeps_the_graph() {
+        for graph in small_graphs() {
+            for permutation in [graph.degree_order(), graph.reverse_cuthill_mckee()] {
+                let renumbered = graph.permuted(&permutation);
+                for (i, j) in graph.edges() {
+                    assert_eq!(renumbered.0[permutation[i]][permutation[j]], graph.0[i][j]);
+                }
+                assert_eq!(renumbered.edges().len(), graph.edges().len());
+            }
+            let by_degree = graph.permuted(&graph.degree_order());
+            assert!(
+                (1..graph.0.len())
+                    .all(|vertex| by_degree.degree(vertex - 1) >= by_degree.degree(vertex))
+            );
+        }
+        // a path numbered out of order comes back in a line
+        let mut path = Graph(Vec::new());
+        (0..7).for_each(|_| {
+            path.add_vertex();
+        });
+        let order = [3, 0, 5, 1, 6, 2, 4];
+        for leg in order.windows(2) {
+            path.set_edge(leg[0], leg[1], Some(1));
         }
+        assert_eq!(path.bandwidth(), 5);
+        assert_eq!(path.permuted(&path.reverse_cuthill_mckee()).bandwidth(), 1);
     }
 
-    pub fn weight_sum(&self) -> usize {
-        self.tree.iter().map(|(_, _, w)| *w as usize).sum()
+    // the matrix `main` prints for the default variant
+    #[test]
+    fn variant_4228() {
+        let variant = Variant::new(4228).unwrap();
+        let generated = Graph::generate(
+            variant.vertex_count(),
+            variant.seed(),
+            variant.k(Lab::SpanningTree),
+        );
+        assert_eq!(
+            generated.0,
+            graph(&[
+                [0, 166, 197, 148, 0, 0, 162, 0, 0, 0, 171, 0],
+                [166, 0, 0, 179, 0, 0, 0, 150, 0, 136, 153, 188],
+                [197, 0, 0, 0, 0, 180, 154, 141, 0, 132, 138, 0],
+                [148, 179, 0, 0, 0, 198, 0, 199, 164, 162, 0, 167],
+                [0, 0, 0, 0, 0, 143, 190, 0, 125, 0, 187, 126],
+                [0, 0, 180, 198, 143, 0, 0, 182, 0, 157, 0, 125],
+                [162, 0, 154, 0, 190, 0, 0, 0, 118, 0, 176, 0],
+                [0, 150, 141, 199, 0, 182, 0, 0, 0, 153, 0, 0],
+                [0, 0, 0, 164, 125, 0, 118, 0, 0, 0, 187, 148],
+                [0, 136, 132, 162, 0, 157, 0, 153, 0, 0, 0, 114],
+                [171, 153, 138, 0, 187, 0, 176, 0, 187, 0, 0, 159],
+                [0, 188, 0, 167, 126, 125, 0, 0, 148, 114, 159, 0],
+            ])
+            .0
+        );
     }
 }
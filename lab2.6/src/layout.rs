@@ -0,0 +1,635 @@
+use std::{collections::VecDeque, f32::consts::PI, fs, io};
+
+use raylib::prelude::*;
+
+use crate::{WIN_MARGIN, draw, variant::Variant};
+
+#[derive(Debug, Clone, Copy)]
+pub struct VertexPos {
+    pub v: Vector2,
+    pub row: usize,
+    pub col: usize,
+}
+
+impl VertexPos {
+    /// A vertex placed by hand, outside of the row layout.
+    pub fn free(v: Vector2) -> Self {
+        VertexPos {
+            v,
+            row: usize::MAX,
+            col: usize::MAX,
+        }
+    }
+
+    pub fn is_free(&self) -> bool {
+        self.row == usize::MAX
+    }
+}
+
+/// Row layout filling a window of `size`.
+pub fn from_rows(rows: &[usize], size: Vector2) -> Vec<VertexPos> {
+    fn current_position(index: usize, rows: &[usize]) -> (usize, usize) {
+        let mut cumulative = 0;
+        for (row, &count) in rows.iter().enumerate() {
+            if index < cumulative + count {
+                return (row, index - cumulative);
+            }
+            cumulative += count;
+        }
+        (usize::MAX, usize::MAX)
+    }
+    let winwidth = size.x * WIN_MARGIN;
+    let winheight = size.y * WIN_MARGIN;
+    let vertex_count = rows.iter().sum();
+    Vec::from_iter((0..vertex_count).map(|i| {
+        let (row, col) = current_position(i, rows);
+
+        let x_offset = (size.x - winwidth) * 0.5;
+        let y_offset = (size.y - winheight) * 0.5;
+        VertexPos {
+            v: Vector2 {
+                x: (winwidth / (rows[row] - 1).max(1) as f32 * col as f32) + x_offset,
+                y: (winheight / (rows.len() - 1).max(1) as f32 * row as f32) + y_offset,
+            },
+            row,
+            col,
+        }
+    }))
+}
+
+/// Follows a window resize from `from` to `to`. Every layout scales linearly
+/// with the window, so this is the same as recomputing it for the new size,
+/// and vertices moved by hand keep their relative place.
+pub fn rescale(vertices: &mut [VertexPos], from: Vector2, to: Vector2) {
+    if from.x <= 0.0 || from.y <= 0.0 {
+        return;
+    }
+    for vertex in vertices.iter_mut() {
+        vertex.v.x *= to.x / from.x;
+        vertex.v.y *= to.y / from.y;
+    }
+}
+
+pub fn vertex_at(vertices: &[VertexPos], point: Vector2) -> Option<usize> {
+    vertices
+        .iter()
+        .position(|vertex| vertex.v.distance_to(point) <= draw::VERTEX_RADIUS)
+}
+
+// edges keep this far from the centers of vertices they do not connect
+const EDGE_CLEARANCE: f32 = 1.25 * draw::VERTEX_RADIUS;
+const BEND_STEP: f32 = 1.5 * draw::VERTEX_RADIUS;
+const BEND_TRIES: usize = 6;
+pub const CURVE_SEGMENTS: usize = 16;
+/// Smallest bend of a directed edge whose reverse edge also exists, so the
+/// two are drawn on opposite sides of the chord.
+pub const ANTIPARALLEL_BEND: f32 = draw::VERTEX_RADIUS;
+
+pub fn distance_to_segment(point: Vector2, from: Vector2, to: Vector2) -> f32 {
+    let segment = to - from;
+    if segment.length_sqr() == 0.0 {
+        return point.distance_to(from);
+    }
+    let t = ((point - from).dot(segment) / segment.length_sqr()).clamp(0.0, 1.0);
+    point.distance_to(from + segment * t)
+}
+
+/// Control point of the quadratic Bézier curve from `from` to `to` whose
+/// middle lies `bend` pixels to the right of the chord (left for negative
+/// `bend`, straight for zero).
+pub fn bezier_control(from: Vector2, to: Vector2, bend: f32) -> Vector2 {
+    let normal = (to - from).normalized().rotated(0.5 * PI);
+    (from + to) * 0.5 + normal * (2.0 * bend)
+}
+
+// $\mathtt{B(t) = (1 - t)^2 P_0 + 2(1 - t)t P_1 + t^2 P_2}$
+pub fn bezier_point(from: Vector2, control: Vector2, to: Vector2, t: f32) -> Vector2 {
+    from * ((1.0 - t) * (1.0 - t)) + control * (2.0 * (1.0 - t) * t) + to * (t * t)
+}
+
+// $\mathtt{B'(t) = 2(1 - t)(P_1 - P_0) + 2t(P_2 - P_1)}$
+pub fn bezier_tangent(from: Vector2, control: Vector2, to: Vector2, t: f32) -> Vector2 {
+    (control - from) * (2.0 * (1.0 - t)) + (to - control) * (2.0 * t)
+}
+
+/// The edge curve between two vertex centers as a polyline.
+pub fn curve(from: Vector2, to: Vector2, bend: f32) -> [Vector2; CURVE_SEGMENTS + 1] {
+    let control = bezier_control(from, to, bend);
+    std::array::from_fn(|i| bezier_point(from, control, to, i as f32 / CURVE_SEGMENTS as f32))
+}
+
+/// How many vertices other than `from` and `to` the edge would pass through.
+fn curve_hits(vertices: &[VertexPos], from: usize, to: usize, bend: f32) -> usize {
+    let points = curve(vertices[from].v, vertices[to].v, bend);
+    (0..vertices.len())
+        .filter(|&k| k != from && k != to)
+        .filter(|&k| {
+            points
+                .windows(2)
+                .any(|s| distance_to_segment(vertices[k].v, s[0], s[1]) < EDGE_CLEARANCE)
+        })
+        .count()
+}
+
+/// Bend for the edge `from -> to` that keeps it clear of every other vertex:
+/// straight when possible, otherwise curving further and further out,
+/// alternating sides. With a positive `min_bend` the edge only curves to its
+/// right and at least that much. Falls back to the candidate that crosses
+/// the fewest vertices.
+pub fn edge_bend(vertices: &[VertexPos], from: usize, to: usize, min_bend: f32) -> f32 {
+    let candidates = (0..=BEND_TRIES).flat_map(|step| {
+        let bend = step as f32 * BEND_STEP;
+        if min_bend > 0.0 {
+            vec![min_bend + bend]
+        } else if step == 0 {
+            vec![0.0]
+        } else {
+            vec![bend, -bend]
+        }
+    });
+    let mut best = (usize::MAX, min_bend.max(0.0));
+    for bend in candidates {
+        let hits = curve_hits(vertices, from, to, bend);
+        if hits == 0 {
+            return bend;
+        } else if hits < best.0 {
+            best = (hits, bend);
+        }
+    }
+    best.1
+}
+
+const EDGE_HIT_DISTANCE: f32 = 6.0;
+
+/// First of `edges` whose curve, routed the way `draw_all_edges` routes it,
+/// passes near `point`. Loops are ignored, so are `to -> from` duplicates of
+/// undirected edges.
+pub fn edge_at(
+    vertices: &[VertexPos],
+    edges: &[(usize, usize)],
+    directed: bool,
+    point: Vector2,
+) -> Option<(usize, usize)> {
+    edges
+        .iter()
+        .copied()
+        .filter(|(from, to)| if directed { from != to } else { from < to })
+        .find(|&(from, to)| {
+            let min_bend = if directed && edges.contains(&(to, from)) {
+                ANTIPARALLEL_BEND
+            } else {
+                0.0
+            };
+            let bend = edge_bend(vertices, from, to, min_bend);
+            curve(vertices[from].v, vertices[to].v, bend)
+                .windows(2)
+                .any(|s| distance_to_segment(point, s[0], s[1]) <= EDGE_HIT_DISTANCE)
+        })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hover {
+    Vertex(usize),
+    Edge(usize, usize),
+}
+
+/// The vertex under `point`, or else the edge.
+pub fn hover(
+    vertices: &[VertexPos],
+    edges: &[(usize, usize)],
+    directed: bool,
+    point: Vector2,
+) -> Option<Hover> {
+    vertex_at(vertices, point).map(Hover::Vertex).or_else(|| {
+        edge_at(vertices, edges, directed, point).map(|(from, to)| Hover::Edge(from, to))
+    })
+}
+
+/// Moves vertices around while the left mouse button is held on them.
+pub struct Drag(Option<usize>);
+
+impl Drag {
+    pub fn new() -> Self {
+        Drag(None)
+    }
+
+    /// `mouse` is the cursor in layout coordinates, see `View::mouse`.
+    pub fn update(&mut self, rl: &RaylibHandle, mouse: Vector2, vertices: &mut [VertexPos]) {
+        if rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT) {
+            self.0 = vertex_at(vertices, mouse);
+        } else if rl.is_mouse_button_released(MouseButton::MOUSE_BUTTON_LEFT) {
+            self.0 = None;
+        } else if let Some(vertex) = self.0
+            && rl.get_mouse_delta().length() > 0.0
+        {
+            vertices[vertex] = VertexPos::free(mouse);
+        }
+    }
+}
+
+/// `layout_4228.txt`, or `layout_4228_<view>.txt` for views with their own vertex set.
+pub fn file_name(variant: &Variant, view: Option<&str>) -> String {
+    match view {
+        Some(view) => format!("layout_{}_{}.txt", variant, view),
+        None => format!("layout_{}.txt", variant),
+    }
+}
+
+// one `x y` line per vertex, `x y row col` if it is still in its row layout slot
+pub fn save(path: &str, vertices: &[VertexPos]) -> io::Result<()> {
+    let contents: String = vertices
+        .iter()
+        .map(|vertex| {
+            if vertex.is_free() {
+                format!("{} {}\n", vertex.v.x, vertex.v.y)
+            } else {
+                format!(
+                    "{} {} {} {}\n",
+                    vertex.v.x, vertex.v.y, vertex.row, vertex.col
+                )
+            }
+        })
+        .collect();
+    fs::write(path, contents)
+}
+
+pub fn load(path: &str) -> io::Result<Vec<VertexPos>> {
+    fn invalid(line: &str) -> io::Error {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("expected `x y [row col]`, got `{}`", line),
+        )
+    }
+
+    fs::read_to_string(path)?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let values: Vec<&str> = line.split_whitespace().collect();
+            if values.len() != 2 && values.len() != 4 {
+                return Err(invalid(line));
+            }
+            let coord = |i: usize| values[i].parse::<f32>().map_err(|_| invalid(line));
+            let index = |i: usize| values[i].parse::<usize>().map_err(|_| invalid(line));
+            let v = Vector2 {
+                x: coord(0)?,
+                y: coord(1)?,
+            };
+            if values.len() == 2 {
+                Ok(VertexPos::free(v))
+            } else {
+                Ok(VertexPos {
+                    v,
+                    row: index(2)?,
+                    col: index(3)?,
+                })
+            }
+        })
+        .collect()
+}
+
+/// Saved layout if there is one for this many vertices, the row layout otherwise.
+pub fn load_or_rows(path: &str, rows: &[usize], size: Vector2) -> Vec<VertexPos> {
+    let vertex_count: usize = rows.iter().sum();
+    match load(path) {
+        Ok(vertices) if vertices.len() == vertex_count => {
+            println!("Layout loaded from {}", path);
+            vertices
+        }
+        Ok(vertices) => {
+            eprintln!(
+                "Ignoring {}: {} vertices saved, graph has {}",
+                path,
+                vertices.len(),
+                vertex_count
+            );
+            from_rows(rows, size)
+        }
+        Err(err) if err.kind() == io::ErrorKind::NotFound => from_rows(rows, size),
+        Err(err) => {
+            eprintln!("Ignoring {}: {}", path, err);
+            from_rows(rows, size)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arrangement {
+    Rows,
+    Circular,
+    ForceDirected,
+    Layered,
+    Tree,
+}
+
+impl Arrangement {
+    pub fn next(self) -> Self {
+        match self {
+            Arrangement::Rows => Arrangement::Circular,
+            Arrangement::Circular => Arrangement::ForceDirected,
+            Arrangement::ForceDirected => Arrangement::Layered,
+            Arrangement::Layered => Arrangement::Tree,
+            Arrangement::Tree => Arrangement::Rows,
+        }
+    }
+}
+
+/// Positions for `rows.iter().sum()` vertices connected by `edges`.
+/// `roots` are only used by the tree arrangement, see `tree`.
+pub fn arrange(
+    arrangement: Arrangement,
+    rows: &[usize],
+    edges: &[(usize, usize)],
+    roots: &[usize],
+    size: Vector2,
+) -> Vec<VertexPos> {
+    let vertex_count = rows.iter().sum();
+    match arrangement {
+        Arrangement::Rows => from_rows(rows, size),
+        Arrangement::Circular => circular(vertex_count, size),
+        Arrangement::ForceDirected => force_directed(vertex_count, edges, size),
+        Arrangement::Layered => layered(vertex_count, edges, size),
+        Arrangement::Tree => tree(vertex_count, edges, roots, size),
+    }
+}
+
+/// Maps a point of the unit square onto a window of `size`, keeping `WIN_MARGIN`.
+fn place(x: f32, y: f32, size: Vector2) -> VertexPos {
+    let winwidth = size.x * WIN_MARGIN;
+    let winheight = size.y * WIN_MARGIN;
+    VertexPos::free(Vector2 {
+        x: x * winwidth + (size.x - winwidth) * 0.5,
+        y: y * winheight + (size.y - winheight) * 0.5,
+    })
+}
+
+/// Stretches arbitrary coordinates over the whole window.
+fn fit(points: &[Vector2], size: Vector2) -> Vec<VertexPos> {
+    let min_x = points.iter().map(|p| p.x).fold(f32::INFINITY, f32::min);
+    let max_x = points.iter().map(|p| p.x).fold(f32::NEG_INFINITY, f32::max);
+    let min_y = points.iter().map(|p| p.y).fold(f32::INFINITY, f32::min);
+    let max_y = points.iter().map(|p| p.y).fold(f32::NEG_INFINITY, f32::max);
+    let normalize = |value: f32, min: f32, max: f32| {
+        if max - min > f32::EPSILON {
+            (value - min) / (max - min)
+        } else {
+            0.5
+        }
+    };
+    points
+        .iter()
+        .map(|p| {
+            place(
+                normalize(p.x, min_x, max_x),
+                normalize(p.y, min_y, max_y),
+                size,
+            )
+        })
+        .collect()
+}
+
+fn unit_circle(vertex_count: usize) -> Vec<Vector2> {
+    (0..vertex_count)
+        .map(|i| {
+            let angle = 2.0 * PI * i as f32 / vertex_count as f32 - 0.5 * PI;
+            Vector2 {
+                x: angle.cos(),
+                y: angle.sin(),
+            }
+        })
+        .collect()
+}
+
+pub fn circular(vertex_count: usize, size: Vector2) -> Vec<VertexPos> {
+    fit(&unit_circle(vertex_count), size)
+}
+
+/// Fruchterman–Reingold, starting from the circular layout so the result is
+/// the same on every run.
+pub fn force_directed(
+    vertex_count: usize,
+    edges: &[(usize, usize)],
+    size: Vector2,
+) -> Vec<VertexPos> {
+    const ITERATIONS: usize = 300;
+    if vertex_count == 0 {
+        return Vec::new();
+    }
+
+    let width = size.x * WIN_MARGIN;
+    let height = size.y * WIN_MARGIN;
+    let k = (width * height / vertex_count as f32).sqrt();
+    let mut positions: Vec<Vector2> = unit_circle(vertex_count)
+        .into_iter()
+        .map(|p| p * (0.5 * width.min(height)))
+        .collect();
+
+    for iteration in 0..ITERATIONS {
+        let temperature = 0.1 * width * (1.0 - iteration as f32 / ITERATIONS as f32);
+        let mut displacement = vec![Vector2 { x: 0.0, y: 0.0 }; vertex_count];
+
+        for i in 0..vertex_count {
+            for j in (i + 1)..vertex_count {
+                let delta = positions[i] - positions[j];
+                let distance = delta.length().max(0.01);
+                let push = delta / distance * (k * k / distance);
+                displacement[i] += push;
+                displacement[j] -= push;
+            }
+        }
+        for &(i, j) in edges.iter().filter(|(i, j)| i != j) {
+            let delta = positions[i] - positions[j];
+            let distance = delta.length().max(0.01);
+            let pull = delta / distance * (distance * distance / k);
+            displacement[i] -= pull;
+            displacement[j] += pull;
+        }
+
+        for i in 0..vertex_count {
+            let length = displacement[i].length();
+            if length > 0.0 {
+                positions[i] += displacement[i] / length * length.min(temperature);
+            }
+            positions[i].x = positions[i].x.clamp(-0.5 * width, 0.5 * width);
+            positions[i].y = positions[i].y.clamp(-0.5 * height, 0.5 * height);
+        }
+    }
+
+    fit(&positions, size)
+}
+
+/// Sugiyama-style layering: cycles are broken by reversing DFS back edges,
+/// every vertex goes one layer below its lowest predecessor, then a few
+/// barycenter sweeps reorder the layers to reduce crossings.
+pub fn layered(vertex_count: usize, edges: &[(usize, usize)], size: Vector2) -> Vec<VertexPos> {
+    const SWEEPS: usize = 4;
+
+    // cycle removal
+    let mut successors = vec![Vec::new(); vertex_count];
+    edges
+        .iter()
+        .filter(|(from, to)| from != to)
+        .for_each(|&(from, to)| successors[from].push(to));
+    let mut state = vec![0_u8; vertex_count]; // 0 - new, 1 - on stack, 2 - done
+    let mut dag: Vec<(usize, usize)> = Vec::with_capacity(edges.len());
+    for root in 0..vertex_count {
+        if state[root] != 0 {
+            continue;
+        }
+        let mut stack = vec![(root, 0)];
+        state[root] = 1;
+        while let Some((vertex, next)) = stack.pop() {
+            if let Some(&to) = successors[vertex].get(next) {
+                stack.push((vertex, next + 1));
+                match state[to] {
+                    0 => {
+                        dag.push((vertex, to));
+                        state[to] = 1;
+                        stack.push((to, 0));
+                    }
+                    1 => dag.push((to, vertex)),
+                    _ => dag.push((vertex, to)),
+                }
+            } else {
+                state[vertex] = 2;
+            }
+        }
+    }
+
+    // longest path layering, in topological order
+    let mut in_degree = vec![0; vertex_count];
+    dag.iter().for_each(|(_, to)| in_degree[*to] += 1);
+    let mut layer = vec![0; vertex_count];
+    let mut queue: VecDeque<usize> = (0..vertex_count).filter(|v| in_degree[*v] == 0).collect();
+    while let Some(vertex) = queue.pop_front() {
+        for &(from, to) in dag.iter().filter(|(from, _)| *from == vertex) {
+            layer[to] = layer[to].max(layer[from] + 1);
+            in_degree[to] -= 1;
+            if in_degree[to] == 0 {
+                queue.push_back(to);
+            }
+        }
+    }
+
+    let layer_count = layer.iter().max().map_or(0, |max| max + 1);
+    let mut layers: Vec<Vec<usize>> = vec![Vec::new(); layer_count];
+    (0..vertex_count).for_each(|v| layers[layer[v]].push(v));
+
+    // crossing reduction
+    let mut order = vec![0.0_f32; vertex_count];
+    let update_order = |layers: &[Vec<usize>], order: &mut [f32]| {
+        layers.iter().for_each(|vertices| {
+            vertices.iter().enumerate().for_each(|(i, v)| {
+                order[*v] = (i as f32 + 0.5) / vertices.len() as f32;
+            })
+        })
+    };
+    update_order(&layers, &mut order);
+    for sweep in 0..SWEEPS {
+        let downwards = sweep % 2 == 0;
+        let indices: Vec<usize> = if downwards {
+            (1..layer_count).collect()
+        } else {
+            (0..layer_count.saturating_sub(1)).rev().collect()
+        };
+        for index in indices {
+            let barycenter = |v: usize| {
+                let neighbours: Vec<f32> = dag
+                    .iter()
+                    .filter_map(|&(from, to)| match downwards {
+                        true if to == v && layer[from] < index => Some(order[from]),
+                        false if from == v && layer[to] > index => Some(order[to]),
+                        _ => None,
+                    })
+                    .collect();
+                if neighbours.is_empty() {
+                    order[v]
+                } else {
+                    neighbours.iter().sum::<f32>() / neighbours.len() as f32
+                }
+            };
+            let mut keyed: Vec<(f32, usize)> =
+                layers[index].iter().map(|v| (barycenter(*v), *v)).collect();
+            keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+            layers[index] = keyed.into_iter().map(|(_, v)| v).collect();
+            update_order(&layers, &mut order);
+        }
+    }
+
+    let mut result = vec![place(0.5, 0.5, size); vertex_count];
+    for (index, vertices) in layers.iter().enumerate() {
+        let y = index as f32 / (layer_count - 1).max(1) as f32;
+        for (i, v) in vertices.iter().enumerate() {
+            result[*v] = place((i as f32 + 0.5) / vertices.len() as f32, y, size);
+        }
+    }
+    result
+}
+
+/// Layered drawing of the spanning forest found by BFS over `edges`, starting
+/// from `roots` and then from the lowest unvisited vertex. Leaves get evenly
+/// spaced columns and each parent is centered above its children.
+pub fn tree(
+    vertex_count: usize,
+    edges: &[(usize, usize)],
+    roots: &[usize],
+    size: Vector2,
+) -> Vec<VertexPos> {
+    let mut adjacent = vec![Vec::new(); vertex_count];
+    for &(from, to) in edges.iter().filter(|(from, to)| from != to) {
+        adjacent[from].push(to);
+        adjacent[to].push(from);
+    }
+    adjacent.iter_mut().for_each(|list| list.sort());
+
+    let mut children = vec![Vec::new(); vertex_count];
+    let mut depth = vec![0; vertex_count];
+    let mut visited = vec![false; vertex_count];
+    let mut forest = Vec::new();
+    for root in roots.iter().copied().chain(0..vertex_count) {
+        if visited[root] {
+            continue;
+        }
+        visited[root] = true;
+        forest.push(root);
+        let mut queue = VecDeque::from([root]);
+        while let Some(vertex) = queue.pop_front() {
+            for &next in &adjacent[vertex] {
+                if !visited[next] {
+                    visited[next] = true;
+                    depth[next] = depth[vertex] + 1;
+                    children[vertex].push(next);
+                    queue.push_back(next);
+                }
+            }
+        }
+    }
+
+    // leaves take the next free column, parents the middle of their children
+    fn assign(vertex: usize, children: &[Vec<usize>], column: &mut [f32], next: &mut f32) {
+        if children[vertex].is_empty() {
+            column[vertex] = *next;
+            *next += 1.0;
+        } else {
+            children[vertex]
+                .iter()
+                .for_each(|child| assign(*child, children, column, next));
+            let first = column[children[vertex][0]];
+            let last = column[*children[vertex].last().unwrap()];
+            column[vertex] = 0.5 * (first + last);
+        }
+    }
+    let mut column = vec![0.0; vertex_count];
+    let mut next = 0.0;
+    forest
+        .iter()
+        .for_each(|root| assign(*root, &children, &mut column, &mut next));
+
+    let max_depth = depth.iter().max().copied().unwrap_or(0).max(1);
+    (0..vertex_count)
+        .map(|v| {
+            let x = (column[v] + 0.5) / next;
+            place(x, depth[v] as f32 / max_depth as f32, size)
+        })
+        .collect()
+}
@@ -1,115 +1,190 @@
 #![allow(clippy::needless_range_loop)]
 
-use std::collections::VecDeque;
-
-use graph::{DEFAULT_ROWS, Graph, KruskalStep, VERTEX_COUNT};
+use editor::Editor;
+use graph::{BoruvkaStep, EdgeState, Graph, KruskalStep, Outcome, ReverseDeleteStep, Spanning};
+use layout::{Arrangement, Hover, VertexPos};
 use raylib::{color::Color, prelude::*};
+use timeline::Timeline;
+use tour::{HELD_KARP_MAX_VERTICES, Tour};
+use variant::{Lab, Variant};
+use view::View;
 mod draw;
+mod editor;
+mod forest;
 mod graph;
+mod layout;
+mod rng;
+mod timeline;
+mod tour;
+mod variant;
+mod view;
 
 const WIN_WIDTH: i32 = 800;
 const WIN_HEIGHT: i32 = 600;
 const WIN_MARGIN: f32 = 0.8;
 const OVERLAY_FONT_SIZE: i32 = 24;
+const TOOLTIP_OFFSET: Vector2 = Vector2 { x: 12.0, y: 12.0 };
+const HISTORY_FONT_SIZE: i32 = 18;
+// latest steps listed on screen
+const HISTORY_ROWS: usize = 8;
 
-const K: f32 = 1.0 - 2.0 * 0.01 - 8.0 * 0.005 - 0.05;
+/// Tree <F9> draws in place of the algorithm's, to weigh it against the MST.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Off,
+    Steiner,
+    ShortestPaths,
+}
 
-#[derive(Debug, Clone, Copy)]
-struct VertexPos {
-    v: Vector2,
-    row: usize,
-    col: usize,
+impl Comparison {
+    fn next(self) -> Self {
+        match self {
+            Comparison::Off => Comparison::Steiner,
+            Comparison::Steiner => Comparison::ShortestPaths,
+            Comparison::ShortestPaths => Comparison::Off,
+        }
+    }
 }
 
-fn draw_all_vertices(d: &mut RaylibDrawHandle, font: &Font, rows: &[usize]) -> Vec<VertexPos> {
-    fn current_position(index: usize, rows: &[usize]) -> (usize, usize) {
-        let mut cumulative = 0;
-        for (row, &count) in rows.iter().enumerate() {
-            if index < cumulative + count {
-                return (row, index - cumulative);
-            }
-            cumulative += count;
-        }
-        (usize::MAX, usize::MAX)
-    }
-    let winwidth = WIN_WIDTH as f32 * WIN_MARGIN;
-    let winheight = WIN_HEIGHT as f32 * WIN_MARGIN;
-    let vertex_count = rows.iter().sum();
-    let vertex_coords: Vec<VertexPos> = Vec::from_iter((0..vertex_count).map(|i| {
-        let (row, col) = current_position(i, rows);
-
-        let x_offset = (WIN_WIDTH as f32 - winwidth) * 0.5;
-        let y_offset = (WIN_HEIGHT as f32 - winheight) * 0.5;
-        VertexPos {
-            v: Vector2 {
-                x: (winwidth / (DEFAULT_ROWS[row] - 1) as f32 * col as f32) + x_offset,
-                y: (winheight / (DEFAULT_ROWS.len() - 1) as f32 * row as f32) + y_offset,
-            },
-            row,
-            col,
+/// Travelling salesman tour <F10> draws, the last one improving the
+/// nearest neighbour tour with 2-opt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TourMethod {
+    Off,
+    HeldKarp,
+    MstDoubling,
+    NearestNeighbour,
+    TwoOpt,
+}
+
+impl TourMethod {
+    fn next(self) -> Self {
+        match self {
+            TourMethod::Off => TourMethod::HeldKarp,
+            TourMethod::HeldKarp => TourMethod::MstDoubling,
+            TourMethod::MstDoubling => TourMethod::NearestNeighbour,
+            TourMethod::NearestNeighbour => TourMethod::TwoOpt,
+            TourMethod::TwoOpt => TourMethod::Off,
         }
-    }));
+    }
 
-    (0..vertex_count).for_each(|i| {
+    fn name(self) -> &'static str {
+        match self {
+            TourMethod::Off => "off",
+            TourMethod::HeldKarp => "Held-Karp",
+            TourMethod::MstDoubling => "MST doubling",
+            TourMethod::NearestNeighbour => "Nearest neighbour",
+            TourMethod::TwoOpt => "2-opt",
+        }
+    }
+}
+
+fn draw_all_vertices(
+    d: &mut impl RaylibDraw,
+    font: &Font,
+    vertex_coords: &[VertexPos],
+    color: impl Fn(usize) -> Color,
+) {
+    (0..vertex_coords.len()).for_each(|i| {
         draw::draw_vertex(
             d,
             vertex_coords[i].v,
             &((i + 1).to_string()),
             font,
-            Color::BLACK,
+            color(i),
         );
     });
+}
+
+/// Colours every algorithm draws its edges with.
+fn state_color(state: EdgeState) -> Color {
+    match state {
+        EdgeState::Pending => Color::BLACK,
+        EdgeState::Considering => Color::BLUE,
+        EdgeState::Tree => Color::RED,
+        EdgeState::Discarded => Color::LIGHTGRAY,
+    }
+}
+
+/// Colour of the edges a step is about, cycles in orange.
+fn outcome_color(outcome: &Outcome) -> Color {
+    match outcome {
+        Outcome::Considered(_) | Outcome::Cheapest { .. } => state_color(EdgeState::Considering),
+        Outcome::Accepted(_) | Outcome::Merged(_) | Outcome::Kept(_) => {
+            state_color(EdgeState::Tree)
+        }
+        Outcome::Rejected { .. } | Outcome::Deleted { .. } => Color::ORANGE,
+        Outcome::Finished { .. } => Color::BLACK,
+    }
+}
 
-    vertex_coords
+/// Edge colours for the step shown: the edge the last step left out and
+/// the cycle that made it go in orange, the rest by their state.
+fn step_colors(step: &dyn Spanning, hide_edges: bool) -> impl Fn(usize, usize) -> Color + '_ {
+    let cycle = match step.history().last() {
+        Some(Outcome::Rejected { edge, cycle }) | Some(Outcome::Deleted { edge, cycle }) => {
+            Some((edge, cycle))
+        }
+        _ => None,
+    };
+    move |i, j| {
+        let state = step.edge_state(i.min(j), i.max(j));
+        if let Some((edge, cycle)) = cycle
+            && (edge.0 == i && edge.1 == j
+                || cycle
+                    .windows(2)
+                    .any(|pair| pair == [i, j] || pair == [j, i]))
+        {
+            Color::ORANGE
+        } else if hide_edges && (state == EdgeState::Pending || state == EdgeState::Discarded) {
+            Color::WHITE.alpha(0.0)
+        } else {
+            state_color(state)
+        }
+    }
+}
+
+/// Colour of the trees <F9> compares and of the vertices they start from.
+fn comparison_color(comparison: Comparison) -> Color {
+    match comparison {
+        Comparison::Off => Color::BLACK,
+        Comparison::Steiner => Color::DARKGREEN,
+        Comparison::ShortestPaths => Color::PURPLE,
+    }
 }
 
 fn draw_all_edges(
-    d: &mut RaylibDrawHandle,
+    d: &mut impl RaylibDraw,
     graph: &Graph,
     vertex_coords: &[VertexPos],
     font: &Font,
     directed: bool,
-    hide_edges: bool,
-    step: &KruskalStep,
+    edge_color: impl Fn(usize, usize) -> Color,
 ) {
     for i in 0..vertex_coords.len() {
         let lower = if directed { 0 } else { i };
         for j in lower..vertex_coords.len() {
             let origin = vertex_coords[i];
             let destination = vertex_coords[j];
-            let row_absdiff = (destination.row as i64 - origin.row as i64).abs();
-            let col_absdiff = (destination.col as i64 - origin.col as i64).abs();
 
             if let Some(weight) = graph.0[i][j] {
-                let step_current = step.current.unwrap_or((usize::MAX, usize::MAX, 0));
-                let color = if step_current.0 == i && step_current.1 == j {
-                    Color::BLUE
-                } else if step
-                    .tree
-                    .iter()
-                    .any(|(row, column, _)| *row == i && *column == j)
-                {
-                    Color::RED
-                } else if hide_edges {
-                    Color::WHITE.alpha(0.0)
-                } else {
-                    Color::BLACK
-                };
-                let mut angled = false;
+                let color = edge_color(i, j);
+                let mut bend = 0.0;
 
                 if i == j {
                     draw::draw_looping_edge(d, origin.v, color);
-                } else if (graph.0[j][i].is_some() && directed) // symmetric
-                    || (row_absdiff == 0 && col_absdiff > 1) // same row, goes through others
-                    || (col_absdiff == 0 && row_absdiff > 1) // same col, goes through others
-                    || (origin.v.x == destination.v.x) // same x coordinate, yes, still possible
-                    || col_absdiff >= 3
-                // honestly ^ whatever this is
-                {
-                    draw::draw_angled_edge(d, origin.v, destination.v, directed, color);
-                    angled = true;
                 } else {
-                    draw::draw_straight_edge(d, origin.v, destination.v, directed, color);
+                    let min_bend = if directed && graph.0[j][i].is_some() {
+                        layout::ANTIPARALLEL_BEND
+                    } else {
+                        0.0
+                    };
+                    bend = layout::edge_bend(vertex_coords, i, j, min_bend);
+                    if bend == 0.0 {
+                        draw::draw_straight_edge(d, origin.v, destination.v, directed, color);
+                    } else {
+                        draw::draw_curved_edge(d, origin.v, destination.v, bend, directed, color);
+                    }
                 }
 
                 if i < j {
@@ -120,7 +195,7 @@ fn draw_all_edges(
                         &weight.to_string(),
                         font,
                         color,
-                        angled,
+                        bend,
                     );
                 }
             }
@@ -128,92 +203,602 @@ fn draw_all_edges(
     }
 }
 
-fn draw_controls(d: &mut RaylibDrawHandle, font: &Font, hide_edges: bool) {
+fn draw_controls(d: &mut impl RaylibDraw, font: &Font, size: Vector2, hide_edges: bool) {
     draw::draw_text(
         d,
         font,
         "<F3> Hide edges",
         Vector2 {
-            x: 0.12 * WIN_WIDTH as f32,
-            y: 0.01 * WIN_HEIGHT as f32,
+            x: 0.12 * size.x,
+            y: 0.01 * size.y,
         },
         OVERLAY_FONT_SIZE as f32,
         if hide_edges { Color::RED } else { Color::BLACK },
     );
+    draw::draw_text(
+        d,
+        font,
+        "<F7> Export log",
+        Vector2 {
+            x: 0.42 * size.x,
+            y: 0.01 * size.y,
+        },
+        OVERLAY_FONT_SIZE as f32,
+        Color::BLACK,
+    );
     draw::draw_text(
         d,
         font,
         "<Space> Step",
         Vector2 {
-            x: 0.1 * WIN_WIDTH as f32,
-            y: 0.96 * WIN_HEIGHT as f32,
+            x: 0.1 * size.x,
+            y: 0.96 * size.y,
         },
         OVERLAY_FONT_SIZE as f32,
         Color::BLACK,
     );
 }
 
+/// The algorithm shown and its latest steps, newest last, in the colours
+/// the edges are drawn with.
+fn draw_history(d: &mut impl RaylibDraw, font: &Font, size: Vector2, step: &dyn Spanning) {
+    let history = step.history();
+    let shown = &history[history.len().saturating_sub(HISTORY_ROWS)..];
+    let title = (format!("<F8> {}", step.name()), Color::BLACK);
+    let lines = shown
+        .iter()
+        .map(|outcome| (outcome.to_string(), outcome_color(outcome)));
+    for (row, (text, color)) in std::iter::once(title).chain(lines).enumerate() {
+        d.draw_text_ex(
+            font,
+            &text,
+            Vector2 {
+                x: 0.01 * size.x,
+                y: 0.07 * size.y + (row as i32 * HISTORY_FONT_SIZE) as f32,
+            },
+            HISTORY_FONT_SIZE as f32,
+            0.0,
+            color,
+        );
+    }
+}
+
+/// Tooltip title first, then the details for the side panel.
+fn inspect(graph: &Graph, step: &dyn Spanning, hover: Hover) -> Vec<String> {
+    match hover {
+        Hover::Vertex(vertex) => {
+            let component = graph
+                .components()
+                .iter()
+                .position(|comp| comp.contains(&vertex))
+                .unwrap_or(0);
+            vec![
+                format!("Vertex {}", vertex + 1),
+                format!("Degree: {}", graph.degree(vertex)),
+                format!("Component: {}", component + 1),
+            ]
+        }
+        Hover::Edge(from, to) => {
+            let weight = graph.0[from][to].unwrap_or(0);
+            let is_edge = |(i, j, _): &(usize, usize, u32)| *i == from && *j == to;
+            let in_mst = graph.minimum_spanning_tree().iter().any(is_edge);
+            vec![
+                format!("Edge {} -- {}", from + 1, to + 1),
+                format!("Weight: {}", weight),
+                format!("{}: {}", step.name(), step.edge_state(from, to).name()),
+                format!("In MST: {}", if in_mst { "yes" } else { "no" }),
+                format!(
+                    "Slack: {}",
+                    graph
+                        .slack(from, to)
+                        .map_or(String::new(), |s| s.to_string())
+                ),
+            ]
+        }
+    }
+}
+
+/// Tree arrangement lays out the minimum spanning tree, the rest use every edge.
+fn arrange(
+    arrangement: Arrangement,
+    variant: &Variant,
+    graph: &Graph,
+    size: Vector2,
+) -> Vec<VertexPos> {
+    let weighted = if arrangement == Arrangement::Tree {
+        graph.minimum_spanning_tree()
+    } else {
+        graph.sorted_edges().into()
+    };
+    let edges: Vec<(usize, usize)> = weighted.iter().map(|(i, j, _)| (*i, *j)).collect();
+    layout::arrange(
+        arrangement,
+        &variant.rows_for(graph.0.len()),
+        &edges,
+        &[],
+        size,
+    )
+}
+
+fn print_graph(graph: &Graph) {
+    println!("Graph:\n{}", graph);
+    print!("Vertex degrees: ");
+    (0..graph.0.len()).for_each(|i| print!("{} ", graph.degree(i)));
+    println!(
+        "\nComponents: {:?}",
+        graph
+            .components()
+            .iter()
+            .map(|comp| comp.iter().map(|i| i + 1).collect::<Vec<_>>())
+            .collect::<Vec<_>>()
+    );
+    if !graph.is_connected() {
+        println!("Disconnected, the algorithms build a minimum spanning forest");
+    }
+    println!(
+        "Unique MST: {}",
+        if graph.has_unique_mst() { "yes" } else { "no" }
+    );
+    match graph.second_best_spanning_tree() {
+        Some(second) => println!("Second-best spanning tree: {}", second),
+        None => println!("No second-best spanning tree, the MST is the only one"),
+    }
+    println!();
+}
+
+/// What <F11> prints: the graph renumbered by degree and by reverse
+/// Cuthill–McKee, the new number of every vertex and the bandwidth before
+/// and after.
+fn print_renumbered(graph: &Graph) {
+    for (name, permutation) in [
+        ("Degree", graph.degree_order()),
+        ("Reverse Cuthill-McKee", graph.reverse_cuthill_mckee()),
+    ] {
+        let renumbered = graph.permuted(&permutation);
+        println!(
+            "{} order, bandwidth {} -> {}:",
+            name,
+            graph.bandwidth(),
+            renumbered.bandwidth()
+        );
+        let mapping: Vec<String> = permutation
+            .iter()
+            .enumerate()
+            .map(|(old, new)| format!("{}->{}", old + 1, new + 1))
+            .collect();
+        println!("{}\n{}", mapping.join(" "), renumbered);
+    }
+}
+
+/// A line for every tree of the finished forest: the vertices it spans,
+/// its edge count and weight.
+fn print_forest(step: &dyn Spanning) {
+    for (index, tree) in step.forest().iter().enumerate() {
+        println!(
+            "Component {}: vertices {:?}, {} edges, weight {}",
+            index + 1,
+            tree.vertices.iter().map(|i| i + 1).collect::<Vec<_>>(),
+            tree.edges.len(),
+            tree.weight
+        );
+    }
+    println!();
+}
+
+/// The <F9> panel: the Steiner tree for `terminals` and the shortest-path
+/// tree from `root` next to the MST, by total weight.
+fn comparison_lines(
+    graph: &Graph,
+    comparison: Comparison,
+    terminals: &[usize],
+    root: usize,
+) -> Vec<String> {
+    let title = match comparison {
+        Comparison::Off => return vec![String::from("<F9> Compare trees")],
+        Comparison::Steiner => "<F9> Steiner tree",
+        Comparison::ShortestPaths => "<F9> Shortest-path tree",
+    };
+    let weight = |tree: &[(usize, usize, u32)]| -> u32 { tree.iter().map(|(_, _, w)| w).sum() };
+    let numbers = |vertices: &[usize]| {
+        vertices
+            .iter()
+            .map(|vertex| (vertex + 1).to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    vec![
+        String::from(title),
+        format!("<T> Terminals: {}", numbers(terminals)),
+        format!("<R> Root: {}", root + 1),
+        format!("Steiner tree: {}", weight(&graph.steiner_tree(terminals))),
+        format!(
+            "Shortest-path tree: {}",
+            weight(&graph.shortest_path_tree(root))
+        ),
+        format!("MST: {}", weight(&graph.minimum_spanning_tree())),
+    ]
+}
+
+/// The tour `method` finds, nearest neighbour starting at `start`, printed
+/// with its cost or why there is none.
+fn find_tour(graph: &Graph, method: TourMethod, start: usize) -> Option<Tour> {
+    let tour = match method {
+        TourMethod::Off => return None,
+        TourMethod::HeldKarp => graph.held_karp_tour(),
+        TourMethod::MstDoubling => graph.mst_doubling_tour(),
+        TourMethod::NearestNeighbour => graph.nearest_neighbour_tour(start),
+        TourMethod::TwoOpt => graph
+            .nearest_neighbour_tour(start)
+            .and_then(|tour| graph.two_opt(tour)),
+    };
+    match &tour {
+        Some(tour) => println!(
+            "{} tour: {} -> {}, cost {}",
+            method.name(),
+            tour.order
+                .iter()
+                .map(|vertex| (vertex + 1).to_string())
+                .collect::<Vec<_>>()
+                .join(" -> "),
+            tour.order[0] + 1,
+            tour.cost
+        ),
+        None if graph.0.is_empty() => println!("No tour of an empty graph"),
+        None if method == TourMethod::HeldKarp && graph.0.len() > HELD_KARP_MAX_VERTICES => {
+            println!(
+                "Held-Karp takes at most {} vertices",
+                HELD_KARP_MAX_VERTICES
+            )
+        }
+        None => println!("No tour, the graph is disconnected"),
+    }
+    tour
+}
+
+fn graph_summary(graph: &Graph) -> String {
+    if graph.0.is_empty() {
+        return String::from("Empty graph");
+    }
+    let mst_weight: u32 = graph
+        .minimum_spanning_tree()
+        .iter()
+        .map(|(_, _, w)| w)
+        .sum();
+    format!(
+        "Components: {}  {}: {} ({})",
+        graph.components().len(),
+        // a disconnected graph only has a spanning forest
+        if graph.is_connected() { "MST" } else { "MSF" },
+        mst_weight,
+        if graph.has_unique_mst() {
+            "unique"
+        } else {
+            "not unique"
+        }
+    )
+}
+
+type Canvas<'a, 'b> = RaylibMode2D<'a, RaylibDrawHandle<'b>>;
+
+/// A recorded run of any of the algorithms, so the window can switch
+/// between them without knowing which one it shows.
+trait Run {
+    fn current(&self) -> &dyn Spanning;
+    fn scrub(&mut self, rl: &RaylibHandle, view: &View) -> bool;
+    /// Handles the timeline keys and steps the algorithm when they ask for
+    /// it, printing what every new step did.
+    fn update(&mut self, rl: &RaylibHandle);
+    fn draw_timeline(&self, d: &mut Canvas, font: &Font, size: Vector2);
+}
+
+impl<S: Spanning + Clone + PartialEq> Run for Timeline<S> {
+    fn current(&self) -> &dyn Spanning {
+        Timeline::<S>::current(self)
+    }
+
+    fn scrub(&mut self, rl: &RaylibHandle, view: &View) -> bool {
+        Timeline::scrub(self, rl, view.overlay(), view.size)
+    }
+
+    fn update(&mut self, rl: &RaylibHandle) {
+        if self.tick(rl) {
+            self.forward(|step| match step.step() {
+                Some(outcome) => {
+                    println!("{}: {}", step.name(), outcome);
+                    if let Outcome::Finished { .. } = outcome {
+                        print_forest(step);
+                    }
+                    true
+                }
+                None => false,
+            });
+        }
+    }
+
+    fn draw_timeline(&self, d: &mut Canvas, font: &Font, size: Vector2) {
+        Timeline::draw(self, d, font, size);
+    }
+}
+
+/// Kruskal, Borůvka and reverse-delete on the same graph, in the order
+/// <F8> cycles through them.
+fn all_algorithms(graph: &Graph) -> Vec<Box<dyn Run>> {
+    vec![
+        Box::new(Timeline::new(KruskalStep::new(graph))),
+        Box::new(Timeline::new(BoruvkaStep::new(graph))),
+        Box::new(Timeline::new(ReverseDeleteStep::new(graph))),
+    ]
+}
+
 fn main() {
+    let variant = Variant::from_args();
+    let rows = variant.rows();
+
     let (mut rl, thread) = raylib::init()
         .size(WIN_WIDTH, WIN_HEIGHT)
         .log_level(TraceLogLevel::LOG_WARNING)
         .title("ASD Lab 2.6")
+        .resizable()
         .build();
+    rl.set_window_min_size(WIN_WIDTH / 2, WIN_HEIGHT / 2);
 
     let font = rl.load_font(&thread, "FiraCode-Regular.ttf").unwrap();
 
-    let matrix = Graph::generate(K);
-    let mut edges: Vec<(usize, usize, u32)> = matrix
-        .0
-        .iter()
-        .enumerate()
-        .flat_map(|(i, row)| {
-            row.iter()
-                .enumerate()
-                .filter_map(move |(j, &cell)| cell.map(|w| (i, j, w)))
-        })
-        .filter(|(i, j, _)| *i < *j)
-        .collect();
-    edges.sort_by_key(|(_, _, w)| *w);
-
-    let mut edges_deque = VecDeque::from(edges);
-    let mut step = KruskalStep::new(VERTEX_COUNT);
+    let mut matrix = Graph::generate(
+        variant.vertex_count(),
+        variant.seed(),
+        variant.k(Lab::SpanningTree),
+    );
+    let mut algorithms = all_algorithms(&matrix);
+    let mut current = 0;
+    let layout_file = layout::file_name(&variant, None);
+    let log_file = format!("mst_log_{}.txt", variant);
+    let mut view = View::new(&rl);
+    let mut vertex_pos = layout::load_or_rows(&layout_file, &rows, view.size);
+    let mut arrangement = Arrangement::Rows;
+    let mut editor = Editor::new();
 
     let mut hide_edges = false;
+    let mut comparison = Comparison::Off;
+    let mut terminals: Vec<usize> = Vec::new();
+    let mut root = 0;
+    let mut tour_method = TourMethod::Off;
+    let mut tour = None;
+    // trees <F9> compares and the panel weighing them, worked out again
+    // only once the graph or what they start from changes
+    let mut compared = Vec::new();
+    let mut comparison_panel = Vec::new();
+    let mut compare_again = true;
+    // algorithm and history length of the step shown, and seconds it has
+    // been shown
+    let mut shown_steps = (0, 0);
+    let mut since_step = 0.0;
 
-    println!("Graph:\n{}", matrix);
+    println!("Variant: {}", variant);
+    print_graph(&matrix);
+    let mut summary = graph_summary(&matrix);
 
     while !rl.window_should_close() {
-        if rl.is_key_pressed(KeyboardKey::KEY_SPACE) {
-            Graph::kruskal_step(&mut edges_deque, &mut step);
-        } else if rl.is_key_pressed(KeyboardKey::KEY_F3) {
-            hide_edges = !hide_edges;
+        if let Some(old_size) = view.update(&rl) {
+            layout::rescale(&mut vertex_pos, old_size, view.size);
+        }
+        let mouse = view.mouse(&rl);
+        let scrubbing = algorithms[current].scrub(&rl, &view);
+        if !scrubbing && editor.update(&mut rl, mouse, &mut matrix, &mut vertex_pos) {
+            algorithms = all_algorithms(&matrix);
+            print_graph(&matrix);
+            summary = graph_summary(&matrix);
+            // removing a vertex renumbers the ones after it
+            terminals.clear();
+            root = 0;
+            tour = find_tour(&matrix, tour_method, root);
+            compare_again = true;
+        }
+        if !editor.is_typing() {
+            algorithms[current].update(&rl);
+            if rl.is_key_pressed(KeyboardKey::KEY_F3) {
+                hide_edges = !hide_edges;
+            } else if rl.is_key_pressed(KeyboardKey::KEY_F6) {
+                arrangement = arrangement.next();
+                println!("Arrangement: {:?}", arrangement);
+                vertex_pos = arrange(arrangement, &variant, &matrix, view.size);
+            } else if rl.is_key_pressed(KeyboardKey::KEY_F5) {
+                match layout::save(&layout_file, &vertex_pos) {
+                    Ok(()) => println!("Layout saved to {}", layout_file),
+                    Err(err) => eprintln!("Could not save {}: {}", layout_file, err),
+                }
+            } else if rl.is_key_pressed(KeyboardKey::KEY_F7) {
+                let step = algorithms[current].current();
+                let log: String = std::iter::once(format!("{}\n", step.name()))
+                    .chain(
+                        step.history()
+                            .iter()
+                            .map(|outcome| format!("{}\n", outcome)),
+                    )
+                    .collect();
+                match std::fs::write(&log_file, log) {
+                    Ok(()) => println!("{} log saved to {}", step.name(), log_file),
+                    Err(err) => eprintln!("Could not save {}: {}", log_file, err),
+                }
+            } else if rl.is_key_pressed(KeyboardKey::KEY_F8) {
+                current = (current + 1) % algorithms.len();
+                println!("Algorithm: {}", algorithms[current].current().name());
+            } else if rl.is_key_pressed(KeyboardKey::KEY_F9) {
+                comparison = comparison.next();
+                println!("Comparison: {:?}", comparison);
+                compare_again = true;
+            } else if rl.is_key_pressed(KeyboardKey::KEY_F10) {
+                tour_method = tour_method.next();
+                tour = find_tour(&matrix, tour_method, root);
+                compare_again = true;
+            } else if rl.is_key_pressed(KeyboardKey::KEY_F11) {
+                print_renumbered(&matrix);
+            } else if let Some(vertex) = layout::vertex_at(&vertex_pos, mouse) {
+                if rl.is_key_pressed(KeyboardKey::KEY_T) {
+                    match terminals.iter().position(|terminal| *terminal == vertex) {
+                        Some(index) => {
+                            terminals.remove(index);
+                        }
+                        None => terminals.push(vertex),
+                    }
+                    println!(
+                        "Terminals: {:?}",
+                        terminals.iter().map(|i| i + 1).collect::<Vec<_>>()
+                    );
+                    compare_again = true;
+                } else if rl.is_key_pressed(KeyboardKey::KEY_R) {
+                    root = vertex;
+                    println!("Root: {}", root + 1);
+                    tour = find_tour(&matrix, tour_method, root);
+                    compare_again = true;
+                }
+            }
+        }
+        let run = &algorithms[current];
+        let step = run.current();
+        // each step, forward or back, replays the pointer moves that led to it
+        if (current, step.history().len()) != shown_steps {
+            shown_steps = (current, step.history().len());
+            since_step = 0.0;
+        } else {
+            since_step += rl.get_frame_time();
+        }
+        let inspection = layout::hover(&vertex_pos, &matrix.edges(), false, mouse)
+            .map(|hover| inspect(&matrix, step, hover));
+        if compare_again {
+            compare_again = false;
+            compared = match comparison {
+                Comparison::Steiner => matrix.steiner_tree(&terminals),
+                Comparison::ShortestPaths if root < matrix.0.len() => {
+                    matrix.shortest_path_tree(root)
+                }
+                _ => Vec::new(),
+            };
+            comparison_panel.clear();
+            if !matrix.0.is_empty() {
+                comparison_panel = comparison_lines(&matrix, comparison, &terminals, root);
+                comparison_panel.push(format!("<F10> Tour: {}", tour_method.name()));
+            }
         }
         let mut d = rl.begin_drawing(&thread);
 
         d.clear_background(Color::WHITE);
-        let vertex_pos = draw_all_vertices(&mut d, &font, DEFAULT_ROWS);
-        draw_all_edges(
+        {
+            let mut d = d.begin_mode2D(view.camera);
+            let starts = |vertex: usize| match comparison {
+                Comparison::Off => false,
+                Comparison::Steiner => terminals.contains(&vertex),
+                Comparison::ShortestPaths => vertex == root,
+            };
+            draw_all_vertices(&mut d, &font, &vertex_pos, |vertex| {
+                if starts(vertex) {
+                    comparison_color(comparison)
+                } else {
+                    Color::BLACK
+                }
+            });
+            if let Some(tour) = &tour {
+                draw_all_edges(&mut d, &matrix, &vertex_pos, &font, false, |i, j| {
+                    if tour
+                        .edges
+                        .iter()
+                        .any(|(from, to, _)| *from == i && *to == j)
+                    {
+                        Color::MAGENTA
+                    } else {
+                        Color::LIGHTGRAY
+                    }
+                });
+            } else if comparison == Comparison::Off {
+                draw_all_edges(
+                    &mut d,
+                    &matrix,
+                    &vertex_pos,
+                    &font,
+                    false,
+                    step_colors(step, hide_edges),
+                );
+            } else {
+                draw_all_edges(&mut d, &matrix, &vertex_pos, &font, false, |i, j| {
+                    if compared.iter().any(|(from, to, _)| *from == i && *to == j) {
+                        comparison_color(comparison)
+                    } else {
+                        Color::LIGHTGRAY
+                    }
+                });
+            }
+            editor.draw(&mut d, &font, mouse, &vertex_pos);
+            if let Some(lines) = &inspection {
+                draw::draw_panel(&mut d, &font, &lines[..1], mouse + TOOLTIP_OFFSET);
+            }
+        }
+
+        let mut d = d.begin_mode2D(view.overlay());
+        draw_controls(&mut d, &font, view.size, hide_edges);
+        run.draw_timeline(&mut d, &font, view.size);
+        draw_history(&mut d, &font, view.size, step);
+        if let Some(uf) = step.union_find() {
+            forest::draw(
+                &mut d,
+                &font,
+                Rectangle {
+                    x: 0.66 * view.size.x,
+                    y: 0.5 * view.size.y,
+                    width: 0.33 * view.size.x,
+                    height: 0.33 * view.size.y,
+                },
+                uf,
+                since_step / forest::ANIMATION_TIME,
+            );
+        }
+        if !comparison_panel.is_empty() {
+            let panel = draw::panel_size(&font, &comparison_panel);
+            let position = Vector2 {
+                x: 0.01 * view.size.x,
+                y: 0.92 * view.size.y - panel.y,
+            };
+            draw::draw_panel(&mut d, &font, &comparison_panel, position);
+        }
+        if let Some(lines) = &inspection {
+            let panel = draw::panel_size(&font, lines);
+            let position = Vector2 {
+                x: 0.99 * view.size.x - panel.x,
+                y: 0.08 * view.size.y,
+            };
+            draw::draw_panel(&mut d, &font, lines, position);
+        }
+        draw::draw_text(
             &mut d,
-            &matrix,
-            &vertex_pos,
             &font,
-            false,
-            hide_edges,
-            &step,
+            &summary,
+            Vector2 {
+                x: 0.6 * view.size.x,
+                y: 0.96 * view.size.y,
+            },
+            OVERLAY_FONT_SIZE as f32,
+            Color::BLACK,
         );
-
-        draw_controls(&mut d, &font, hide_edges);
+        let (cost, cost_color) = match (tour_method, &tour) {
+            (TourMethod::Off, _) => (
+                String::from("Weight sum: ") + &step.weight_sum().to_string(),
+                Color::BLUE,
+            ),
+            (_, Some(tour)) => (
+                String::from("Tour cost: ") + &tour.cost.to_string(),
+                Color::MAGENTA,
+            ),
+            (_, None) => (String::from("No tour"), Color::MAGENTA),
+        };
         draw::draw_text(
             &mut d,
             &font,
-            &(String::from("Weight sum: ") + &step.weight_sum().to_string()),
+            &cost,
             Vector2 {
-                x: 0.8 * WIN_WIDTH as f32,
-                y: 0.01 * WIN_HEIGHT as f32,
+                x: 0.8 * view.size.x,
+                y: 0.01 * view.size.y,
             },
             OVERLAY_FONT_SIZE as f32,
-            Color::BLUE,
+            cost_color,
         );
     }
 }
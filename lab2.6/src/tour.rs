@@ -0,0 +1,255 @@
+use crate::graph::Graph;
+
+// beyond this the 2^n subsets take too long and too much memory
+pub const HELD_KARP_MAX_VERTICES: usize = 16;
+
+/// A closed walk through every vertex. The graph need not have an edge
+/// between every two vertices, so consecutive ones in `order` are joined by
+/// a shortest path, and `edges` are the edges those paths take.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tour {
+    pub order: Vec<usize>,
+    pub cost: u32,
+    pub edges: Vec<(usize, usize, u32)>,
+}
+
+impl Graph {
+    /// Shortest distance between every two vertices, the complete graph
+    /// every tour is planned on. `None` if the graph is disconnected, no
+    /// tour visits every vertex then.
+    fn distances(&self) -> Option<Vec<Vec<u32>>> {
+        (0..self.0.len())
+            .map(|vertex| self.shortest_paths(vertex).0.into_iter().collect())
+            .collect()
+    }
+
+    fn tour(&self, order: Vec<usize>, distances: &[Vec<u32>]) -> Tour {
+        let legs = || order.iter().zip(order.iter().cycle().skip(1));
+        let mut edges: Vec<(usize, usize, u32)> = Vec::new();
+        for (from, to) in legs() {
+            let (_, previous) = self.shortest_paths(*from);
+            let mut vertex = *to;
+            while let Some(before) = previous[vertex] {
+                let (i, j) = (vertex.min(before), vertex.max(before));
+                if !edges.iter().any(|(a, b, _)| *a == i && *b == j) {
+                    edges.push((i, j, self.0[i][j].unwrap()));
+                }
+                vertex = before;
+            }
+        }
+        Tour {
+            cost: legs().map(|(from, to)| distances[*from][*to]).sum(),
+            order,
+            edges,
+        }
+    }
+
+    /// The cheapest tour, by dynamic programming over the subsets of
+    /// vertices a path from the first vertex has visited and the vertex it
+    /// ends at. `None` past `HELD_KARP_MAX_VERTICES`.
+    pub fn held_karp_tour(&self) -> Option<Tour> {
+        let size = self.0.len();
+        if size == 0 || size > HELD_KARP_MAX_VERTICES {
+            return None;
+        }
+        let distances = self.distances()?;
+        // $\mathtt{cost[S][v] = \min_{u \in S \setminus \{v\}} cost[S \setminus \{v\}][u] + d(u, v)}$
+        let mut cost = vec![vec![u32::MAX; size]; 1 << size];
+        let mut previous = vec![vec![0; size]; 1 << size];
+        cost[1][0] = 0;
+        for visited in (1..1usize << size).filter(|visited| visited & 1 == 1) {
+            for last in (0..size).filter(|last| visited >> last & 1 == 1) {
+                if cost[visited][last] == u32::MAX {
+                    continue;
+                }
+                for next in (0..size).filter(|next| visited >> next & 1 == 0) {
+                    let extended = visited | 1 << next;
+                    let through = cost[visited][last] + distances[last][next];
+                    if through < cost[extended][next] {
+                        cost[extended][next] = through;
+                        previous[extended][next] = last;
+                    }
+                }
+            }
+        }
+        let all = (1 << size) - 1;
+        let mut last = (0..size)
+            .min_by_key(|last| cost[all][*last].saturating_add(distances[*last][0]))
+            .unwrap();
+        let mut visited = all;
+        let mut order = Vec::with_capacity(size);
+        while visited != 1 {
+            order.push(last);
+            let before = previous[visited][last];
+            visited &= !(1 << last);
+            last = before;
+        }
+        order.push(0);
+        order.reverse();
+        Some(self.tour(order, &distances))
+    }
+
+    /// Walks around the minimum spanning tree, going down each edge and
+    /// back, and skips the vertices it has already been to. Shortcuts never
+    /// cost more than the walk, so the tour costs at most twice the tree,
+    /// which is no heavier than the cheapest tour.
+    pub fn mst_doubling_tour(&self) -> Option<Tour> {
+        let distances = self.distances()?;
+        if distances.is_empty() {
+            return None;
+        }
+        let tree = self.minimum_spanning_tree();
+        let mut order = Vec::with_capacity(distances.len());
+        let mut stack = vec![0];
+        while let Some(vertex) = stack.pop() {
+            if order.contains(&vertex) {
+                continue;
+            }
+            order.push(vertex);
+            // pushed in reverse, so lower numbers come out first
+            let mut children: Vec<usize> = tree
+                .iter()
+                .filter_map(|(i, j, _)| match (*i == vertex, *j == vertex) {
+                    (true, _) => Some(*j),
+                    (_, true) => Some(*i),
+                    _ => None,
+                })
+                .filter(|child| !order.contains(child))
+                .collect();
+            children.sort_by(|a, b| b.cmp(a));
+            stack.extend(children);
+        }
+        Some(self.tour(order, &distances))
+    }
+
+    /// Starts at `start` and always goes on to the closest vertex not
+    /// visited yet.
+    pub fn nearest_neighbour_tour(&self, start: usize) -> Option<Tour> {
+        let distances = self.distances()?;
+        if start >= distances.len() {
+            return None;
+        }
+        let mut order = vec![start];
+        while let Some(next) = (0..distances.len())
+            .filter(|vertex| !order.contains(vertex))
+            .min_by_key(|vertex| distances[*order.last().unwrap()][*vertex])
+        {
+            order.push(next);
+        }
+        Some(self.tour(order, &distances))
+    }
+
+    /// Reverses stretches of `tour` for as long as that makes it cheaper:
+    /// the legs `a -- b` and `c -- e` become `a -- c` and `b -- e`, which
+    /// undoes every crossing. `None` if the graph is disconnected.
+    pub fn two_opt(&self, tour: Tour) -> Option<Tour> {
+        let distances = self.distances()?;
+        let mut order = tour.order;
+        let size = order.len();
+        let mut improved = true;
+        while improved {
+            improved = false;
+            for i in 0..size.saturating_sub(2) {
+                // with i = 0 the last leg leads back to a, nothing to swap
+                for j in (i + 2)..size - (i == 0) as usize {
+                    let (a, b) = (order[i], order[i + 1]);
+                    let (c, e) = (order[j], order[(j + 1) % size]);
+                    if distances[a][c] + distances[b][e] < distances[a][b] + distances[c][e] {
+                        order[i + 1..=j].reverse();
+                        improved = true;
+                    }
+                }
+            }
+        }
+        Some(self.tour(order, &distances))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Connected graphs of up to 8 vertices.
+    fn small_graphs() -> impl Iterator<Item = Graph> {
+        (0..200)
+            .map(|seed| Graph::generate(1 + seed as usize % 8, seed, 1.2))
+            .filter(Graph::is_connected)
+    }
+
+    /// Every vertex order from 0 on.
+    fn orders(rest: Vec<usize>) -> Vec<Vec<usize>> {
+        if rest.is_empty() {
+            return vec![vec![0]];
+        }
+        (0..rest.len())
+            .flat_map(|index| {
+                let mut others = rest.clone();
+                let vertex = others.remove(index);
+                orders(others).into_iter().map(move |mut order| {
+                    order.push(vertex);
+                    order
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn held_karp_is_optimal() {
+        for graph in small_graphs() {
+            let distances = graph.distances().unwrap();
+            let cheapest = orders((1..graph.0.len()).collect())
+                .into_iter()
+                .map(|order| {
+                    let legs = order.iter().zip(order.iter().cycle().skip(1));
+                    legs.map(|(from, to)| distances[*from][*to]).sum::<u32>()
+                })
+                .min()
+                .unwrap();
+            assert_eq!(graph.held_karp_tour().unwrap().cost, cheapest);
+        }
+    }
+
+    #[test]
+    fn mst_doubling_within_twice_the_tree() {
+        for graph in small_graphs() {
+            let tree: u32 = graph
+                .minimum_spanning_tree()
+                .iter()
+                .map(|(_, _, w)| w)
+                .sum();
+            assert!(graph.mst_doubling_tour().unwrap().cost <= 2 * tree);
+        }
+    }
+
+    #[test]
+    fn two_opt_never_costs_more() {
+        for graph in small_graphs() {
+            for tour in [
+                graph.mst_doubling_tour().unwrap(),
+                graph.nearest_neighbour_tour(graph.0.len() - 1).unwrap(),
+            ] {
+                let cost = tour.cost;
+                let improved = graph.two_opt(tour).unwrap();
+                assert!(improved.cost <= cost);
+                let mut visited = improved.order.clone();
+                visited.sort();
+                assert_eq!(visited, (0..graph.0.len()).collect::<Vec<_>>());
+            }
+        }
+    }
+
+    #[test]
+    fn disconnected_has_no_tour() {
+        let mut graph = Graph::generate(5, 1, 1.2);
+        graph.add_vertex();
+        let tour = Tour {
+            order: (0..6).collect(),
+            cost: 0,
+            edges: Vec::new(),
+        };
+        assert!(graph.held_karp_tour().is_none());
+        assert!(graph.mst_doubling_tour().is_none());
+        assert!(graph.nearest_neighbour_tour(0).is_none());
+        assert!(graph.two_opt(tour).is_none());
+    }
+}
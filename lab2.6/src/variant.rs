@@ -0,0 +1,121 @@
+use std::fmt::Display;
+
+pub const DEFAULT_VARIANT: u32 = 4228;
+
+#[allow(dead_code)] // every lab only constructs its own ids
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lab {
+    /// 2.3, graph representation.
+    Representation,
+    /// 2.4, graph properties.
+    Properties,
+    /// 2.4, modified directed graph.
+    PropertiesModified,
+    /// 2.5, BFS and DFS.
+    Traversal,
+    /// 2.6, minimum spanning tree.
+    SpanningTree,
+}
+
+// $\mathtt{\overline{n_1 n_2 n_3 n_4}}$
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Variant {
+    pub number: u32,
+    pub n: [u32; 4],
+}
+
+impl Display for Variant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.number)
+    }
+}
+
+impl Variant {
+    /// `None` unless `number` has four digits.
+    pub fn new(number: u32) -> Option<Self> {
+        if !(1000..=9999).contains(&number) {
+            return None;
+        }
+        Some(Variant {
+            number,
+            n: [
+                number / 1000,
+                number / 100 % 10,
+                number / 10 % 10,
+                number % 10,
+            ],
+        })
+    }
+
+    /// Reads the variant from the first command line argument, falling back to
+    /// `DEFAULT_VARIANT`.
+    pub fn from_args() -> Self {
+        std::env::args()
+            .nth(1)
+            .map_or(Some(DEFAULT_VARIANT), |arg| arg.parse().ok())
+            .and_then(Variant::new)
+            .expect("variant must be a four-digit number, e.g. 4228")
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.number as u64
+    }
+
+    // $\mathtt{10 + n_3}$
+    pub fn vertex_count(&self) -> usize {
+        10 + self.n[2] as usize
+    }
+
+    /// Vertex count per row of the drawn layout: a rectangle, with a vertex in
+    /// the center for `n_4 >= 6`. Circle and triangle placements are
+    /// approximated by the same rectangle.
+    pub fn rows(&self) -> Vec<usize> {
+        self.rows_for(self.vertex_count())
+    }
+
+    /// Same placement as `rows`, for a graph that has since gained or lost vertices.
+    pub fn rows_for(&self, vertex_count: usize) -> Vec<usize> {
+        let middle = if self.n[3] >= 6 { 3 } else { 2 }.min(vertex_count);
+        let perimeter = vertex_count - middle;
+        vec![perimeter / 2, middle, perimeter - perimeter / 2]
+    }
+
+    pub fn k(&self, lab: Lab) -> f32 {
+        let n3 = self.n[2] as f32;
+        let n4 = self.n[3] as f32;
+        match lab {
+            // $\mathtt{1 - n_3 * 0.02 - n_4 * 0.005 - 0.25}$
+            Lab::Representation => 1.0 - n3 * 0.02 - n4 * 0.005 - 0.25,
+            // $\mathtt{1 - n_3 * 0.01 - n_4 * 0.01 - 0.3}$
+            Lab::Properties => 1.0 - n3 * 0.01 - n4 * 0.01 - 0.3,
+            // $\mathtt{1 - n_3 * 0.005 - n_4 * 0.005 - 0.27}$
+            Lab::PropertiesModified => 1.0 - n3 * 0.005 - n4 * 0.005 - 0.27,
+            // $\mathtt{1 - n_3 * 0.01 - n_4 * 0.005 - 0.15}$
+            Lab::Traversal => 1.0 - n3 * 0.01 - n4 * 0.005 - 0.15,
+            // $\mathtt{1 - n_3 * 0.01 - n_4 * 0.005 - 0.05}$
+            Lab::SpanningTree => 1.0 - n3 * 0.01 - n4 * 0.005 - 0.05,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn four_digits_only() {
+        assert_eq!(
+            Variant::new(4228).map(|variant| variant.n),
+            Some([4, 2, 2, 8])
+        );
+        assert_eq!(
+            Variant::new(1000).map(|variant| variant.n),
+            Some([1, 0, 0, 0])
+        );
+        assert!(Variant::new(9999).is_some());
+        assert_eq!(Variant::new(0), None);
+        assert_eq!(Variant::new(228), None);
+        assert_eq!(Variant::new(999), None);
+        assert_eq!(Variant::new(10000), None);
+    }
+}